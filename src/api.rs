@@ -21,12 +21,20 @@ quick_error! {
         IO(err: String) {
             display("IO error: {}", err)
             description("IO error")
-            from(e: io::Error) -> (format!("{}", e))
+        }
+        /// Output closed before all the values were written, e.g. piped into `head`.
+        BrokenPipe {
+            display("Broken pipe")
+            description("Broken pipe")
+        }
+        /// Output file exists and overwriting wasn't requested.
+        FileExists(path: PathBuf) {
+            display("File already exists: {}, use --force to overwrite", path.display())
+            description("File already exists")
         }
         CSV(err: String) {
             display("CSV error: {}", err)
             description("CSV error")
-            from(e: csv::Error) -> (format!("{}", e))
         }
         Filter(err: String) {
             display("Filter error: {}", err)
@@ -38,9 +46,53 @@ quick_error! {
             display("Invalid argument: {}", name)
             description("Invalid argument")
         }
+        /// Unknown column error, with the most similar column names.
+        UnknownColumn(name: String, suggestions: Vec<String>) {
+            display("Unknown column '{}'{}", name, did_you_mean(suggestions))
+            description("Unknown column")
+        }
+        /// Nested column path selected for an output that needs whole fields.
+        NestedColumn(name: String) {
+            display("Nested column '{}' can't be written to parquet, select its top-level field instead", name)
+            description("Nested column")
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::BrokenPipe => Error::BrokenPipe,
+            _ => Error::IO(format!("{}", e)),
+        }
     }
 }
 
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        match e.kind() {
+            csv::ErrorKind::Io(err) if err.kind() == io::ErrorKind::BrokenPipe => {
+                Error::BrokenPipe
+            }
+            _ => Error::CSV(format!("{}", e)),
+        }
+    }
+}
+
+#[inline]
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+
+    let names = suggestions
+        .iter()
+        .map(|s| format!("'{}'", s))
+        .collect::<Vec<_>>();
+
+    format!(", did you mean {}?", names.join(" or "))
+}
+
 /// A specialized `Result` for all errors.
 pub type Result<T> = result::Result<T, Error>;
 
@@ -52,7 +104,7 @@ pub(in crate) mod tests {
     use self::chrono::{Local, TimeZone};
     use self::tempfile::{Builder, NamedTempFile, TempDir};
     use std::iter;
-    use std::{fs, path::Path, rc::Rc};
+    use std::{fs, io, path::Path, rc::Rc};
 
     use parquet::{
         column::writer::ColumnWriter,
@@ -87,6 +139,28 @@ pub(in crate) mod tests {
         }
         ";
 
+    pub static NESTED_MESSAGE_SCHEMA: &str = "
+        message nested_message {
+            REQUIRED INT32 id;
+            OPTIONAL group address {
+                OPTIONAL BYTE_ARRAY city (UTF8);
+                OPTIONAL INT32 zip;
+            }
+            OPTIONAL group events (LIST) {
+                REPEATED group list {
+                    OPTIONAL group element {
+                        OPTIONAL BYTE_ARRAY type (UTF8);
+                    }
+                }
+            }
+            OPTIONAL group tags (LIST) {
+                REPEATED group list {
+                    OPTIONAL BYTE_ARRAY element (UTF8);
+                }
+            }
+        }
+        ";
+
     pub fn time_to_str(value: u64) -> String {
         let dt = Local.timestamp((value / 1000) as i64, 0);
         let s = format!("{}", dt.format("%Y-%m-%d %H:%M:%S %:z"));
@@ -163,13 +237,68 @@ pub(in crate) mod tests {
     }
 
     pub fn write_simple_messages_parquet(path: &Path, vec: &[SimpleMessage]) {
+        write_simple_row_groups_parquet(path, &[vec]);
+    }
+
+    pub fn write_simple_row_groups_parquet(path: &Path, groups: &[&[SimpleMessage]]) {
         let schema = Rc::new(parse_message_type(SIMPLE_MESSSAGE_SCHEMA).unwrap());
         let props = Rc::new(WriterProperties::builder().build());
         let file = fs::File::create(path).unwrap();
         let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+
+        for vec in groups {
+            let mut row_group_writer = writer.next_row_group().unwrap();
+
+            write_simple_row_group(&mut row_group_writer, vec);
+
+            writer.close_row_group(row_group_writer).unwrap();
+        }
+
+        writer.close().unwrap();
+    }
+
+    /// Writes two rows:
+    ///
+    /// ```text
+    /// {id: 1, address: {city: "Paris", zip: 75}, events: [{type: "click"}, {type: "view"}], tags: ["a"]}
+    /// {id: 2, address: null, events: [], tags: null}
+    /// ```
+    pub fn write_nested_messages_parquet(path: &Path) {
+        let schema = Rc::new(parse_message_type(NESTED_MESSAGE_SCHEMA).unwrap());
+        let props = Rc::new(WriterProperties::builder().build());
+        let file = fs::File::create(path).unwrap();
+        let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
         let mut row_group_writer = writer.next_row_group().unwrap();
+        let strings =
+            |vec: &[&str]| vec.iter().map(|s| ByteArray::from(*s)).collect::<Vec<_>>();
+        let mut index = 0;
 
-        write_simple_row_group(&mut row_group_writer, vec);
+        while let Some(mut col_writer) = row_group_writer.next_column().unwrap() {
+            match (index, &mut col_writer) {
+                (0, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[1, 2], None, None)
+                }
+                (1, ColumnWriter::ByteArrayColumnWriter(typed)) => {
+                    typed.write_batch(&strings(&["Paris"]), Some(&[2, 0]), None)
+                }
+                (2, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[75], Some(&[2, 0]), None)
+                }
+                (3, ColumnWriter::ByteArrayColumnWriter(typed)) => typed.write_batch(
+                    &strings(&["click", "view"]),
+                    Some(&[4, 4, 1]),
+                    Some(&[0, 1, 0]),
+                ),
+                (4, ColumnWriter::ByteArrayColumnWriter(typed)) => {
+                    typed.write_batch(&strings(&["a"]), Some(&[3, 0]), Some(&[0, 0]))
+                }
+                _ => unreachable!(),
+            }
+            .unwrap();
+
+            row_group_writer.close_column(col_writer).unwrap();
+            index += 1;
+        }
 
         writer.close_row_group(row_group_writer).unwrap();
         writer.close().unwrap();
@@ -206,4 +335,46 @@ pub(in crate) mod tests {
             Int96::from(vec)
         });
     }
+
+    #[test]
+    fn test_api_broken_pipe_from() {
+        let broken = io::Error::from(io::ErrorKind::BrokenPipe);
+        let other = io::Error::new(io::ErrorKind::InvalidData, "oops");
+
+        assert_eq!(super::Error::BrokenPipe, super::Error::from(broken));
+        assert_eq!(
+            super::Error::IO(String::from("oops")),
+            super::Error::from(other)
+        );
+
+        let broken = csv::Error::from(io::Error::from(io::ErrorKind::BrokenPipe));
+        let other = csv::Error::from(io::Error::new(io::ErrorKind::InvalidData, "oops"));
+
+        assert_eq!(super::Error::BrokenPipe, super::Error::from(broken));
+        assert_eq!(
+            super::Error::CSV(String::from("oops")),
+            super::Error::from(other)
+        );
+    }
+
+    #[test]
+    fn test_api_unknown_column_display() {
+        let none = super::Error::UnknownColumn(String::from("foo"), vec![]);
+        let one =
+            super::Error::UnknownColumn(String::from("nme"), vec![String::from("name")]);
+        let two = super::Error::UnknownColumn(
+            String::from("ab"),
+            vec![String::from("a"), String::from("abc")],
+        );
+
+        assert_eq!("Unknown column 'foo'", none.to_string());
+        assert_eq!(
+            "Unknown column 'nme', did you mean 'name'?",
+            one.to_string()
+        );
+        assert_eq!(
+            "Unknown column 'ab', did you mean 'a' or 'abc'?",
+            two.to_string()
+        );
+    }
 }
@@ -1,10 +1,16 @@
 use crate::api::{Error, Result};
-use crate::output::OutputFormat;
-use clap::ArgMatches;
+use crate::filter::Expr;
+use crate::output::{self, CsvOptions, OutputFormat, TableOptions};
+use crate::sink::{self, ParquetSink};
+use crate::value::{BinaryFormat, TextOptions, TimestampFormat};
+use chrono::format::{Item, StrftimeItems};
+use chrono_tz::Tz;
+use clap::{Arg, ArgMatches};
+use csv::QuoteStyle;
 use regex::Regex;
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 
 /// Gets the value of a specific argument
@@ -70,6 +76,14 @@ pub fn filter_values(
     }
 }
 
+/// Gets the value of a specific argument
+/// Parsing the ArgMatches value as a `crate::filter::Expr`.
+///
+/// If the option wasn't present `None` or `Err(crate::api::Error::Filter)` when invalid.
+pub fn expression_value(matches: &ArgMatches, name: &str) -> Result<Option<Expr>> {
+    matches.value_of(name).map(Expr::parse).transpose()
+}
+
 /// Gets the value of a specific argument
 /// Converting the ArgMatches value to a usize.
 ///
@@ -84,6 +98,35 @@ pub fn usize_value(matches: &ArgMatches, name: &str) -> Result<usize> {
         .ok_or_else(|| Error::InvalidArgument(name.to_string()))
 }
 
+/// Gets the value of a specific argument
+/// Converting the ArgMatches value to a u64 seed.
+///
+/// If the option wasn't present `None` or `crate::api::Error::InvalidArgument`
+/// when invalid.
+pub fn seed_value(matches: &ArgMatches, name: &str) -> Result<Option<u64>> {
+    matches
+        .value_of(name)
+        .map(|v| {
+            v.parse()
+                .map_err(|_| Error::InvalidArgument(name.to_string()))
+        })
+        .transpose()
+}
+
+/// Gets the value of a specific argument
+/// Converting the ArgMatches value to a fraction between 0 and 1.
+///
+/// If the option wasn't present `None` or `crate::api::Error::InvalidArgument`
+/// when invalid.
+pub fn fraction_value(matches: &ArgMatches, name: &str) -> Result<Option<f64>> {
+    matches
+        .value_of(name)
+        .map(|v| {
+            fraction_from_str(v).ok_or_else(|| Error::InvalidArgument(name.to_string()))
+        })
+        .transpose()
+}
+
 /// Gets the value of a specific argument
 /// Converting the ArgMatches value to a `crate::output::OutputFormat`.
 ///
@@ -99,6 +142,189 @@ pub fn output_format_value(matches: &ArgMatches, name: &str) -> Result<OutputFor
         .ok_or_else(|| Error::InvalidArgument(name.to_string()))
 }
 
+/// Gets the parquet sink configured by the `output`, `force`, `compression` and
+/// `row-group-size` arguments.
+///
+/// Returns `None` unless the `format` argument is parquet, or
+/// `crate::api::Error::InvalidArgument` when any of the arguments is invalid.
+pub fn parquet_sink_value(matches: &ArgMatches) -> Result<Option<ParquetSink>> {
+    if matches.value_of("format") != Some(sink::FORMAT) {
+        return Ok(None);
+    }
+
+    let path = matches
+        .value_of("output")
+        .map(PathBuf::from)
+        .ok_or_else(|| Error::InvalidArgument(String::from("output")))?;
+    let compression = matches
+        .value_of("compression")
+        .ok_or_else(|| Error::InvalidArgument(String::from("compression")))
+        .and_then(sink::compression_from_str)?;
+    let row_group_size = usize_value(matches, "row-group-size")?;
+    let sink = ParquetSink::new(path)
+        .with_compression(compression)
+        .with_row_group_size(row_group_size)
+        .with_force(matches.is_present("force"));
+
+    Ok(Some(sink))
+}
+
+/// Arguments of the csv and tsv output formats.
+pub fn csv_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("delimiter")
+            .validator(validate_delimiter)
+            .help("Field delimiter of the csv formats, defaults to ',' or '\\t' for tsv")
+            .takes_value(true)
+            .long("delimiter"),
+        Arg::with_name("quote-style")
+            .help("Quoting of fields in the csv formats")
+            .possible_values(&["necessary", "always", "never", "non-numeric"])
+            .default_value("necessary")
+            .long("quote-style"),
+        Arg::with_name("no-header")
+            .help("Omit the header row in the csv formats")
+            .long("no-header"),
+    ]
+}
+
+#[inline]
+fn delimiter_byte(value: &str) -> Option<u8> {
+    match value.as_bytes() {
+        b"\\t" => Some(b'\t'),
+        [byte] if byte.is_ascii() => Some(*byte),
+        _ => None,
+    }
+}
+
+/// Gets the csv and tsv options from the arguments defined by `csv_args`.
+///
+/// Returns `crate::api::Error::InvalidArgument` when any of the arguments is invalid.
+pub fn csv_options_value(matches: &ArgMatches) -> Result<CsvOptions> {
+    let delimiter = match matches.value_of("delimiter") {
+        Some(value) => Some(
+            delimiter_byte(value)
+                .ok_or_else(|| Error::InvalidArgument(String::from("delimiter")))?,
+        ),
+        None => None,
+    };
+    let quote_style = match matches.value_of("quote-style") {
+        None | Some("necessary") => QuoteStyle::Necessary,
+        Some("always") => QuoteStyle::Always,
+        Some("never") => QuoteStyle::Never,
+        Some("non-numeric") => QuoteStyle::NonNumeric,
+        Some(_) => return Err(Error::InvalidArgument(String::from("quote-style"))),
+    };
+
+    Ok(CsvOptions {
+        delimiter,
+        quote_style,
+        headers: !matches.is_present("no-header"),
+    })
+}
+
+/// Arguments of the tabular output format.
+pub fn table_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("max-width")
+            .validator(validate_number)
+            .help("Max width of each column in the tabular format")
+            .takes_value(true)
+            .long("max-width"),
+        Arg::with_name("no-truncate")
+            .help("Never truncate cells in the tabular format")
+            .long("no-truncate"),
+    ]
+}
+
+/// Gets the tabular options from the arguments defined by `table_args`.
+///
+/// Cells are fitted to the terminal width when stdout is a TTY.
+pub fn table_options_value(matches: &ArgMatches) -> Result<TableOptions> {
+    let max_width = match matches.value_of("max-width") {
+        Some(_) => Some(usize_value(matches, "max-width")?),
+        None => None,
+    };
+
+    Ok(TableOptions {
+        max_width,
+        truncate: !matches.is_present("no-truncate"),
+        terminal_width: output::terminal_width(),
+    })
+}
+
+/// Arguments used when converting values to text.
+pub fn text_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("null")
+            .help("Text of null values, defaults to 'null' or empty in the csv formats")
+            .takes_value(true)
+            .long("null"),
+        Arg::with_name("timezone")
+            .validator(validate_timezone)
+            .help("Time zone of dates and timestamps, e.g. UTC, defaults to local time")
+            .takes_value(true)
+            .long("timezone"),
+        Arg::with_name("timestamp-format")
+            .validator(validate_timestamp_format)
+            .help(
+                "Format of dates and timestamps, iso8601, epoch_ms or a strftime pattern",
+            )
+            .takes_value(true)
+            .long("timestamp-format"),
+        Arg::with_name("binary")
+            .help("Format of binary values, byte lists by default")
+            .possible_values(&["hex", "base64", "utf8-lossy"])
+            .takes_value(true)
+            .long("binary"),
+    ]
+}
+
+#[inline]
+fn timestamp_format_from_str(value: &str) -> Option<TimestampFormat> {
+    match value {
+        "iso8601" => Some(TimestampFormat::Iso8601),
+        "epoch_ms" => Some(TimestampFormat::EpochMillis),
+        pattern => Some(TimestampFormat::Strftime(pattern.to_string()))
+            .filter(|_| StrftimeItems::new(pattern).all(|i| i != Item::Error)),
+    }
+}
+
+/// Gets the text options from the arguments defined by `text_args`.
+///
+/// Returns `crate::api::Error::InvalidArgument` when any of the arguments is invalid.
+pub fn text_options_value(matches: &ArgMatches) -> Result<TextOptions> {
+    let timezone = match matches.value_of("timezone") {
+        Some(value) => Some(
+            value
+                .parse::<Tz>()
+                .map_err(|_| Error::InvalidArgument(String::from("timezone")))?,
+        ),
+        None => None,
+    };
+    let timestamp_format =
+        match matches.value_of("timestamp-format") {
+            Some(value) => Some(timestamp_format_from_str(value).ok_or_else(|| {
+                Error::InvalidArgument(String::from("timestamp-format"))
+            })?),
+            None => None,
+        };
+    let binary = match matches.value_of("binary") {
+        None => None,
+        Some("hex") => Some(BinaryFormat::Hex),
+        Some("base64") => Some(BinaryFormat::Base64),
+        Some("utf8-lossy") => Some(BinaryFormat::Utf8Lossy),
+        Some(_) => return Err(Error::InvalidArgument(String::from("binary"))),
+    };
+
+    Ok(TextOptions {
+        null: matches.value_of("null").map(String::from),
+        timezone,
+        timestamp_format,
+        binary,
+    })
+}
+
 pub fn validate_number(value: String) -> std::result::Result<(), String> {
     value
         .parse::<usize>()
@@ -106,6 +332,54 @@ pub fn validate_number(value: String) -> std::result::Result<(), String> {
         .map_err(|err| err.to_string())
 }
 
+pub fn validate_positive_number(value: String) -> std::result::Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err(String::from("expected a number greater than 0")),
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[inline]
+fn fraction_from_str(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|f| *f >= 0.0 && *f <= 1.0)
+}
+
+pub fn validate_fraction(value: String) -> std::result::Result<(), String> {
+    fraction_from_str(&value).map(|_| ()).ok_or_else(|| {
+        format!(
+            "Invalid fraction '{}', expected a number between 0 and 1",
+            value
+        )
+    })
+}
+
+pub fn validate_seed(value: String) -> std::result::Result<(), String> {
+    value
+        .parse::<u64>()
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+pub fn validate_delimiter(value: String) -> std::result::Result<(), String> {
+    delimiter_byte(&value).map(|_| ()).ok_or_else(|| {
+        format!("Invalid delimiter '{}', expected a single character", value)
+    })
+}
+
+pub fn validate_timezone(value: String) -> std::result::Result<(), String> {
+    value
+        .parse::<Tz>()
+        .map(|_| ())
+        .map_err(|_| format!("Unknown time zone '{}'", value))
+}
+
+pub fn validate_timestamp_format(value: String) -> std::result::Result<(), String> {
+    timestamp_format_from_str(&value)
+        .map(|_| ())
+        .ok_or_else(|| format!("Invalid timestamp format '{}'", value))
+}
+
 pub fn validate_path(value: String) -> std::result::Result<(), String> {
     Some(Path::new(&value))
         .filter(|p| p.exists())
@@ -133,6 +407,12 @@ pub fn validate_filter(value: String) -> std::result::Result<(), String> {
         })
 }
 
+pub fn validate_expression(value: String) -> std::result::Result<(), String> {
+    Expr::parse(&value)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +444,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_args_validate_positive_number() {
+        assert_eq!(Ok(()), validate_positive_number(String::from("1")));
+        assert_eq!(
+            Err("expected a number greater than 0".to_string()),
+            validate_positive_number(String::from("0"))
+        );
+        assert_eq!(
+            Err("invalid digit found in string".to_string()),
+            validate_positive_number(String::from("NOT VALID"))
+        );
+    }
+
     #[test]
     fn test_args_validate_filter() {
         assert_eq!(Ok(()), validate_filter(String::from("foo:bar")));
@@ -202,6 +495,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_args_validate_expression() {
+        assert_eq!(
+            Ok(()),
+            validate_expression(String::from("a = 1 OR b ~ 'c'"))
+        );
+
+        assert_eq!(
+            Err("Filter error: Expected literal at position 4".to_string()),
+            validate_expression(String::from("a = "))
+        );
+    }
+
+    #[test]
+    fn test_args_expression_value() {
+        let name = "where";
+        let valid = create_matches(name, "a IS NULL");
+        let missing = create_mult_matches(name, &[name]);
+
+        assert_eq!(
+            Ok(Some(Expr::IsNull(String::from("a")))),
+            expression_value(&valid, name)
+        );
+        assert_eq!(Ok(None), expression_value(&missing, name));
+    }
+
     #[test]
     fn test_args_usize_value() {
         let name = "limit";
@@ -215,6 +534,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_args_seed_value() {
+        let name = "seed";
+        let valid = create_matches(name, "18446744073709551615");
+        let invalid = create_matches(name, "18446744073709551616");
+        let missing = create_mult_matches(name, &[name]);
+
+        assert_eq!(Ok(Some(u64::MAX)), seed_value(&valid, name));
+        assert_eq!(Ok(None), seed_value(&missing, name));
+        assert_eq!(
+            Err(Error::InvalidArgument("seed".to_string())),
+            seed_value(&invalid, name)
+        );
+    }
+
+    #[test]
+    fn test_args_validate_seed() {
+        assert_eq!(Ok(()), validate_seed(String::from("42")));
+        assert_eq!(true, validate_seed(String::from("NOT VALID")).is_err());
+    }
+
+    #[test]
+    fn test_args_fraction_value() {
+        let name = "fraction";
+        let valid = create_matches(name, "0.25");
+        let invalid = create_matches(name, "1.5");
+        let missing = create_mult_matches(name, &[name]);
+
+        assert_eq!(Ok(Some(0.25)), fraction_value(&valid, name));
+        assert_eq!(Ok(None), fraction_value(&missing, name));
+        assert_eq!(
+            Err(Error::InvalidArgument("fraction".to_string())),
+            fraction_value(&invalid, name)
+        );
+    }
+
+    #[test]
+    fn test_args_validate_fraction() {
+        assert_eq!(Ok(()), validate_fraction(String::from("0")));
+        assert_eq!(Ok(()), validate_fraction(String::from("0.01")));
+        assert_eq!(Ok(()), validate_fraction(String::from("1")));
+        assert_eq!(
+            Err(String::from(
+                "Invalid fraction '-0.1', expected a number between 0 and 1"
+            )),
+            validate_fraction(String::from("-0.1"))
+        );
+        assert_eq!(true, validate_fraction(String::from("NaN")).is_err());
+        assert_eq!(true, validate_fraction(String::from("half")).is_err());
+    }
+
     #[test]
     fn test_args_output_format_value() {
         let name = "format";
@@ -228,6 +598,116 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_args_validate_delimiter() {
+        assert_eq!(Ok(()), validate_delimiter(String::from(";")));
+        assert_eq!(Ok(()), validate_delimiter(String::from("\\t")));
+        assert_eq!(
+            Err("Invalid delimiter 'ab', expected a single character".to_string()),
+            validate_delimiter(String::from("ab"))
+        );
+    }
+
+    #[test]
+    fn test_args_csv_options_value() {
+        let app = App::new("csv").args(&csv_args());
+        let defaults = app.clone().get_matches_from_safe(vec!["csv"]).unwrap();
+        let custom = app
+            .get_matches_from_safe(vec![
+                "csv",
+                "--delimiter=\\t",
+                "--quote-style=always",
+                "--no-header",
+            ])
+            .unwrap();
+
+        let options = csv_options_value(&defaults).unwrap();
+
+        assert_eq!(None, options.delimiter);
+        assert_eq!(true, options.headers);
+
+        let options = csv_options_value(&custom).unwrap();
+
+        assert_eq!(Some(b'\t'), options.delimiter);
+        assert_eq!(true, matches!(options.quote_style, QuoteStyle::Always));
+        assert_eq!(false, options.headers);
+    }
+
+    #[test]
+    fn test_args_validate_timezone() {
+        assert_eq!(Ok(()), validate_timezone(String::from("UTC")));
+        assert_eq!(Ok(()), validate_timezone(String::from("America/Sao_Paulo")));
+        assert_eq!(
+            Err("Unknown time zone 'Mars/Olympus'".to_string()),
+            validate_timezone(String::from("Mars/Olympus"))
+        );
+    }
+
+    #[test]
+    fn test_args_validate_timestamp_format() {
+        assert_eq!(Ok(()), validate_timestamp_format(String::from("iso8601")));
+        assert_eq!(Ok(()), validate_timestamp_format(String::from("epoch_ms")));
+        assert_eq!(
+            Ok(()),
+            validate_timestamp_format(String::from("%Y/%m/%d %H:%M"))
+        );
+        assert_eq!(
+            Err("Invalid timestamp format '%Q'".to_string()),
+            validate_timestamp_format(String::from("%Q"))
+        );
+    }
+
+    #[test]
+    fn test_args_text_options_value() {
+        let app = App::new("text").args(&text_args());
+        let defaults = app.clone().get_matches_from_safe(vec!["text"]).unwrap();
+        let custom = app
+            .get_matches_from_safe(vec![
+                "text",
+                "--null=NULL",
+                "--timezone=UTC",
+                "--timestamp-format=%Y",
+                "--binary=hex",
+            ])
+            .unwrap();
+
+        let options = text_options_value(&defaults).unwrap();
+
+        assert_eq!(None, options.null);
+        assert_eq!(None, options.timezone);
+        assert_eq!(None, options.timestamp_format);
+        assert_eq!(None, options.binary);
+
+        let options = text_options_value(&custom).unwrap();
+
+        assert_eq!(Some(String::from("NULL")), options.null);
+        assert_eq!(Some("UTC".parse::<Tz>().unwrap()), options.timezone);
+        assert_eq!(
+            Some(TimestampFormat::Strftime(String::from("%Y"))),
+            options.timestamp_format
+        );
+        assert_eq!(Some(BinaryFormat::Hex), options.binary);
+    }
+
+    #[test]
+    fn test_args_table_options_value() {
+        let app = App::new("table").args(&table_args());
+        let defaults = app.clone().get_matches_from_safe(vec!["table"]).unwrap();
+        let custom = app
+            .get_matches_from_safe(vec!["table", "--max-width=20", "--no-truncate"])
+            .unwrap();
+
+        let options = table_options_value(&defaults).unwrap();
+
+        assert_eq!(None, options.max_width);
+        assert_eq!(true, options.truncate);
+
+        let options = table_options_value(&custom).unwrap();
+
+        assert_eq!(Some(20), options.max_width);
+        assert_eq!(false, options.truncate);
+    }
+
     #[test]
     fn test_args_string_values() {
         let name = "values";
@@ -2,12 +2,21 @@ use crate::api::Result;
 use crate::command::args;
 use crate::output::{OutputFormat, OutputWriter};
 use crate::reader::ParquetFile;
+use crate::value::Value;
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::io::Write;
 
 pub fn def() -> App<'static, 'static> {
     SubCommand::with_name("count")
         .about("Show num of rows")
+        .arg(
+            Arg::with_name("where")
+                .validator(args::validate_expression)
+                .help("Filter rows using an expression")
+                .takes_value(true)
+                .long("where")
+                .short("w"),
+        )
         .arg(
             Arg::with_name("format")
                 .help("Output format")
@@ -16,6 +25,8 @@ pub fn def() -> App<'static, 'static> {
                 .long("format")
                 .short("f"),
         )
+        .args(&args::csv_args())
+        .args(&args::table_args())
         .arg(
             Arg::with_name("path")
                 .validator(args::validate_path)
@@ -27,15 +38,27 @@ pub fn def() -> App<'static, 'static> {
 
 pub fn run<W: Write>(matches: &ArgMatches, out: &mut W) -> Result<()> {
     let format = args::output_format_value(matches, "format")?;
+    let expression = args::expression_value(matches, "where")?;
     let path = args::path_value(matches, "path")?;
-    let parquet = ParquetFile::from(path);
-    let count = parquet.num_rows();
+    let count = match expression {
+        Some(expr) => ParquetFile::from(path)
+            .with_fields(Some(expr.columns()))
+            .with_expression(Some(expr))
+            .iter()
+            .try_fold(0, |count, row| row.map(|_| count + 1))?,
+        None => ParquetFile::from(path).num_rows(),
+    };
 
     let headers = vec![String::from("COUNT")];
-    let values = vec![Ok(vec![format!("{}", count)])];
+    let values = vec![Ok(vec![Value::UInt(count as u64)])];
 
     let iter = values.into_iter();
-    let mut writer = OutputWriter::new(headers, iter).format(format);
+    let csv_options = args::csv_options_value(matches)?;
+    let table_options = args::table_options_value(matches)?;
+    let mut writer = OutputWriter::new(headers, iter)
+        .format(format)
+        .csv_options(csv_options)
+        .table_options(table_options);
 
     writer.write(out)
 }
@@ -68,6 +91,31 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_count_simple_messages_with_where() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let expected = "COUNT\n3\n";
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(5);
+        let arg_vec = vec![
+            "count",
+            parquet.path().to_str().unwrap(),
+            "-w=field_string ~ '^odd'",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_count_simple_messages_vertical_format() {
         let mut output = Cursor::new(Vec::new());
@@ -109,4 +157,25 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_count_simple_messages_json_format() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let expected = "{\"COUNT\":2}\n";
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(2);
+        let arg_vec = vec!["count", parquet.path().to_str().unwrap(), "-f=json"];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(actual, expected);
+    }
 }
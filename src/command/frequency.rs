@@ -1,52 +1,293 @@
-use crate::api::Result;
+use crate::api::{Error, Result};
 use crate::command::args;
 use crate::output::{OutputFormat, OutputWriter};
-use crate::reader::ParquetFile;
+use crate::path::{self, Segment};
+use crate::reader::{self, ParquetFile};
+use crate::value::{TextOptions, Value};
 use clap::{App, Arg, ArgMatches, SubCommand};
-use stats::Frequencies;
+use either::Either;
+use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use parquet::schema::types::Type;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::io::Write;
 
-fn compute<I>(num_fields: usize, iter: I) -> Result<Vec<Frequencies<String>>>
+/// Value of the row summarizing the values left out by `--top`.
+const OTHER: &str = "(other)";
+
+/// Order of the values of each column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Order {
+    /// Most frequent first.
+    Desc,
+    /// Least frequent first.
+    Asc,
+    /// Sorted by value.
+    Value,
+}
+
+impl Order {
+    fn values() -> Vec<&'static str> {
+        vec!["desc", "asc", "value"]
+    }
+}
+
+impl TryFrom<&str> for Order {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_ref() {
+            "desc" => Ok(Order::Desc),
+            "asc" => Ok(Order::Asc),
+            "value" => Ok(Order::Value),
+            _ => Err(Error::InvalidArgument(value.to_string())),
+        }
+    }
+}
+
+/// Number of rows of a value, or of a combination of values with `--group-by`.
+#[derive(Debug, PartialEq)]
+struct Count {
+    values: Vec<Value>,
+    count: u64,
+    /// Sum of the `--sum` column.
+    sum: Option<f64>,
+}
+
+/// Counts each distinct value of the columns, values are told apart by their text.
+fn compute<I>(
+    num_fields: usize,
+    iter: I,
+    options: &TextOptions,
+) -> Result<Vec<Vec<Count>>>
 where
-    I: Iterator<Item = Result<Vec<String>>>,
+    I: Iterator<Item = Result<Vec<Value>>>,
 {
-    let mut vec: Vec<_> = (0..num_fields).map(|_| Frequencies::new()).collect();
+    let mut vec: Vec<_> = (0..num_fields).map(|_| HashMap::new()).collect();
 
     for row in iter {
-        for (i, val) in row?.iter().enumerate() {
-            vec[i].add(val.to_string());
+        for (i, val) in row?.into_iter().enumerate() {
+            let count = vec[i]
+                .entry(val.display(options).to_string())
+                .or_insert_with(|| Count {
+                    values: vec![val],
+                    count: 0,
+                    sum: None,
+                });
+
+            count.count += 1;
         }
     }
 
-    Ok(vec)
+    Ok(vec
+        .into_iter()
+        .map(|m| m.into_iter().map(|t| t.1).collect())
+        .collect())
 }
 
-fn format_row(field: &str, name: &str, count: u64) -> Vec<String> {
-    vec![field.to_string(), name.to_string(), count.to_string()]
+/// Counts each distinct combination of the first `num_fields` values of the rows,
+/// summing the value that follows them when `sum` is set.
+fn compute_groups<I>(
+    num_fields: usize,
+    sum: bool,
+    iter: I,
+    options: &TextOptions,
+) -> Result<Vec<Count>>
+where
+    I: Iterator<Item = Result<Vec<Value>>>,
+{
+    let mut groups = HashMap::new();
+
+    for row in iter {
+        let mut values = row?;
+        let value = values.get(num_fields).and_then(Value::as_f64);
+
+        values.truncate(num_fields);
+
+        let key = values
+            .iter()
+            .map(|v| v.display(options).to_string())
+            .collect::<Vec<_>>();
+        let group = groups.entry(key).or_insert_with(|| Count {
+            values,
+            count: 0,
+            sum: Some(0.0).filter(|_| sum),
+        });
+
+        group.count += 1;
+        group.sum = group.sum.map(|s| s + value.unwrap_or(0.0));
+    }
+
+    Ok(groups.into_iter().map(|t| t.1).collect())
 }
 
-fn format_rows(
-    fields: Vec<String>,
-    vec: Vec<Frequencies<String>>,
-) -> impl Iterator<Item = Result<Vec<String>>> {
-    vec.into_iter()
-        .enumerate()
-        .map(move |t| {
-            let header = (&fields[t.0]).to_string();
-            let counts =
-                t.1.least_frequent()
-                    .into_iter()
-                    .map(|c| (c.0.to_string(), c.1))
-                    .collect::<Vec<_>>();
-
-            (header, counts)
+/// Checks that the `--sum` column holds numbers, instead of summing nothing.
+fn check_sum_column(schema: &Type, name: &str) -> Result<()> {
+    let column = path::resolve(schema, name)
+        .ok_or_else(|| reader::unknown_column(name, &path::names(schema)))?;
+    let field = column.field;
+    let info = field.get_basic_info();
+    let is_single = column.segments.iter().all(|s| *s != Segment::Elements)
+        && info.has_repetition()
+        && info.repetition() != Repetition::REPEATED;
+    let is_numeric = field.is_primitive()
+        && match (field.get_physical_type(), info.logical_type()) {
+            (_, LogicalType::DECIMAL) => true,
+            (PhysicalType::FLOAT, _) | (PhysicalType::DOUBLE, _) => true,
+            (PhysicalType::INT32, logical) | (PhysicalType::INT64, logical) => matches!(
+                logical,
+                LogicalType::NONE
+                    | LogicalType::INT_8
+                    | LogicalType::INT_16
+                    | LogicalType::INT_32
+                    | LogicalType::INT_64
+                    | LogicalType::UINT_8
+                    | LogicalType::UINT_16
+                    | LogicalType::UINT_32
+                    | LogicalType::UINT_64
+            ),
+            _ => false,
+        };
+
+    if !is_single || !is_numeric {
+        return Err(Error::InvalidArgument(format!(
+            "--sum column '{}' of type {} is not numeric",
+            column.name,
+            column.type_name()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Compares the values of two counts, values that can't be compared, like values of
+/// different types, are compared by their text.
+fn compare_values(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            a.compare(b)
+                .unwrap_or_else(|| a.to_string().cmp(&b.to_string()))
         })
-        .flat_map(|t| {
-            let header = t.0.to_string();
-            let counts = t.1.into_iter();
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Sorts the counts, ties are sorted by value so the output is stable.
+fn sort_counts(counts: &mut [Count], order: Order) {
+    let by_value = |a: &Count, b: &Count| compare_values(&a.values, &b.values);
+
+    match order {
+        Order::Desc => {
+            counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| by_value(a, b)))
+        }
+        Order::Asc => {
+            counts.sort_by(|a, b| a.count.cmp(&b.count).then_with(|| by_value(a, b)))
+        }
+        Order::Value => counts.sort_by(by_value),
+    }
+}
+
+/// Sums the counts into a single `(other)` row, its values are plain text so they can't
+/// be mistaken for a value of the column.
+fn other_count(
+    counts: &[Count],
+    num_values: usize,
+) -> (Vec<Either<String, Value>>, u64, Option<f64>) {
+    let mut values = vec![Either::Left(String::new()); num_values];
+    let sums = counts.iter().filter_map(|c| c.sum).collect::<Vec<_>>();
+
+    if let Some(first) = values.first_mut() {
+        *first = Either::Left(String::from(OTHER));
+    }
+
+    (
+        values,
+        counts.iter().map(|c| c.count).sum(),
+        Some(sums.iter().sum()).filter(|_| !sums.is_empty()),
+    )
+}
 
-            counts.map(move |c| Ok(format_row(&header, &c.0, c.1)))
+/// Percentage of the total as a decimal with two digits.
+#[inline]
+fn percent_value(count: u64, total: u64) -> Value {
+    let percent = 10_000.0 * count as f64 / total as f64;
+
+    Value::Decimal(percent.round() as i128, 2)
+}
+
+fn format_row(
+    field: Option<&str>,
+    values: Vec<Either<String, Value>>,
+    count: u64,
+    sum: Option<f64>,
+    cumulative: u64,
+    total: u64,
+) -> Vec<Either<String, Value>> {
+    field
+        .map(|f| Either::Left(f.to_string()))
+        .into_iter()
+        .chain(values)
+        .chain(vec![
+            Either::Right(Value::UInt(count)),
+            Either::Right(percent_value(count, total)),
+            Either::Right(percent_value(cumulative, total)),
+        ])
+        .chain(sum.map(|s| Either::Right(Value::Double(s))))
+        .collect()
+}
+
+/// Formats the counts of a column, or of the groups when there's no field, the counts
+/// past `top` are summed into a single row.
+fn format_counts(
+    field: Option<&str>,
+    mut counts: Vec<Count>,
+    order: Order,
+    top: Option<usize>,
+) -> Vec<Vec<Either<String, Value>>> {
+    let total = counts.iter().map(|c| c.count).sum::<u64>();
+    let num_values = counts.first().map(|c| c.values.len()).unwrap_or(0);
+    let mut cumulative = 0;
+
+    sort_counts(&mut counts, order);
+
+    let other = match top {
+        Some(top) if top < counts.len() => {
+            Some(other_count(&counts.split_off(top), num_values))
+        }
+        _ => None,
+    };
+
+    counts
+        .into_iter()
+        .map(|c| {
+            (
+                c.values.into_iter().map(Either::Right).collect(),
+                c.count,
+                c.sum,
+            )
         })
+        .chain(other)
+        .map(|(values, count, sum)| {
+            cumulative += count;
+
+            format_row(field, values, count, sum, cumulative, total)
+        })
+        .collect()
+}
+
+fn format_rows(
+    fields: Vec<String>,
+    vec: Vec<Vec<Count>>,
+    order: Order,
+    top: Option<usize>,
+) -> impl Iterator<Item = Result<Vec<Either<String, Value>>>> {
+    fields
+        .into_iter()
+        .zip(vec)
+        .flat_map(move |t| format_counts(Some(&t.0), t.1, order, top))
+        .map(Ok)
 }
 
 pub fn def() -> App<'static, 'static> {
@@ -60,6 +301,22 @@ pub fn def() -> App<'static, 'static> {
                 .multiple(true)
                 .short("c"),
         )
+        .arg(
+            Arg::with_name("group-by")
+                .help("Count the combinations of values of the columns, instead of each column")
+                .takes_value(true)
+                .conflicts_with("columns")
+                .long("group-by")
+                .multiple(true)
+                .short("g"),
+        )
+        .arg(
+            Arg::with_name("sum")
+                .help("Sum a numeric column for each combination of --group-by")
+                .takes_value(true)
+                .requires("group-by")
+                .long("sum"),
+        )
         .arg(
             Arg::with_name("search")
                 .validator(args::validate_filter)
@@ -69,6 +326,14 @@ pub fn def() -> App<'static, 'static> {
                 .multiple(true)
                 .short("s"),
         )
+        .arg(
+            Arg::with_name("where")
+                .validator(args::validate_expression)
+                .help("Filter rows using an expression")
+                .takes_value(true)
+                .long("where")
+                .short("w"),
+        )
         .arg(
             Arg::with_name("limit")
                 .validator(args::validate_number)
@@ -77,6 +342,26 @@ pub fn def() -> App<'static, 'static> {
                 .long("limit")
                 .short("l"),
         )
+        .arg(
+            Arg::with_name("top")
+                .validator(args::validate_positive_number)
+                .help("Max number of values of each column, the rest are counted as (other)")
+                .takes_value(true)
+                .long("top")
+                .short("t"),
+        )
+        .arg(
+            Arg::with_name("order")
+                .help("Order of the values of each column")
+                .possible_values(&Order::values())
+                .default_value("desc")
+                .long("order"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .help("Show row groups skipped using statistics")
+                .long("verbose"),
+        )
         .arg(
             Arg::with_name("format")
                 .help("Output format")
@@ -85,6 +370,9 @@ pub fn def() -> App<'static, 'static> {
                 .long("format")
                 .short("f"),
         )
+        .args(&args::csv_args())
+        .args(&args::table_args())
+        .args(&args::text_args())
         .arg(
             Arg::with_name("path")
                 .validator(args::validate_path)
@@ -96,36 +384,279 @@ pub fn def() -> App<'static, 'static> {
 
 pub fn run<W: Write>(matches: &ArgMatches, out: &mut W) -> Result<()> {
     let format = args::output_format_value(matches, "format")?;
-    let columns = args::string_values(matches, "columns")?;
+    let group_by = args::string_values(matches, "group-by")?;
+    let sum = matches.value_of("sum").map(String::from);
     let search = args::filter_values(matches, "search")?;
+    let expression = args::expression_value(matches, "where")?;
     let limit = args::usize_value(matches, "limit")?;
+    let top = match matches.value_of("top") {
+        Some(_) => Some(args::usize_value(matches, "top")?),
+        None => None,
+    };
+    let order = matches
+        .value_of("order")
+        .ok_or_else(|| Error::InvalidArgument(String::from("order")))
+        .and_then(Order::try_from)?;
+    let text_options = args::text_options_value(matches)?;
     let path = args::path_value(matches, "path")?;
+
+    // the summed column is read right after the group columns
+    let columns = match group_by {
+        Some(ref group_by) => Some(group_by.iter().cloned().chain(sum.clone()).collect()),
+        None => args::string_values(matches, "columns")?,
+    };
     let parquet = ParquetFile::from(path)
         .with_fields(columns)
-        .with_filters(search);
+        .with_filters(search)
+        .with_expression(expression);
+
+    if let Some(ref name) = sum {
+        check_sum_column(&parquet.schema()?, name)?;
+    }
 
     let fields = parquet.field_names()?;
     let rows = parquet.iter().take(limit);
-    let vec = compute(fields.len(), rows)?;
-    let headers = vec![
-        String::from("FIELD"),
-        String::from("VALUE"),
-        String::from("COUNT"),
-    ];
+    let (headers, iter): (_, Box<dyn Iterator<Item = Result<Vec<_>>>>) = match group_by {
+        Some(group_by) => {
+            let num_fields = group_by.len();
+            let counts = compute_groups(num_fields, sum.is_some(), rows, &text_options)?;
+            let headers = fields
+                .into_iter()
+                .take(num_fields)
+                .chain(vec![
+                    String::from("COUNT"),
+                    String::from("PERCENT"),
+                    String::from("CUMULATIVE"),
+                ])
+                .chain(sum.map(|_| String::from("SUM")))
+                .collect();
+            let iter = format_counts(None, counts, order, top).into_iter().map(Ok);
+
+            (headers, Box::new(iter))
+        }
+        None => {
+            let vec = compute(fields.len(), rows, &text_options)?;
+            let headers = vec![
+                String::from("FIELD"),
+                String::from("VALUE"),
+                String::from("COUNT"),
+                String::from("PERCENT"),
+                String::from("CUMULATIVE"),
+            ];
+
+            (headers, Box::new(format_rows(fields, vec, order, top)))
+        }
+    };
+
+    let csv_options = args::csv_options_value(matches)?;
+    let table_options = args::table_options_value(matches)?;
+    let mut writer = OutputWriter::new(headers, iter)
+        .format(format)
+        .csv_options(csv_options)
+        .table_options(table_options)
+        .text_options(text_options);
 
-    let iter = format_rows(fields, vec);
-    let mut writer = OutputWriter::new(headers, iter).format(format);
+    writer.write(out)?;
 
-    writer.write(out)
+    if matches.is_present("verbose") {
+        let (skipped, total) = parquet.skipped_row_groups();
+
+        eprintln!("Skipped {} of {} row groups", skipped, total);
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api;
+    use crate::output::Cell;
     use std::io::Cursor;
     use std::str;
 
+    fn format_texts(rows: Vec<Vec<Either<String, Value>>>) -> Vec<Vec<String>> {
+        let options = TextOptions::default();
+
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|c| c.to_field(&options).unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn create_counts(values: &[(i64, u64)]) -> Vec<Count> {
+        values
+            .iter()
+            .map(|t| Count {
+                values: vec![Value::Int(t.0)],
+                count: t.1,
+                sum: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_frequency_sort_counts() {
+        let mut counts = create_counts(&[(2, 1), (10, 3), (9, 1), (100, 2)]);
+        let mut sorted = |order| {
+            sort_counts(&mut counts, order);
+
+            counts
+                .iter()
+                .map(|c| c.values[0].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // numbers are sorted by value, not by their text
+        assert_eq!(vec!["10", "100", "2", "9"], sorted(Order::Desc));
+        assert_eq!(vec!["2", "9", "100", "10"], sorted(Order::Asc));
+        assert_eq!(vec!["2", "9", "10", "100"], sorted(Order::Value));
+    }
+
+    #[test]
+    fn test_frequency_compare_values() {
+        let text = |s: &str| Value::Str(String::from(s));
+
+        assert_eq!(
+            Ordering::Less,
+            compare_values(&[Value::Int(9)], &[Value::Double(10.5)])
+        );
+        assert_eq!(
+            Ordering::Greater,
+            compare_values(&[text("b"), Value::Int(1)], &[text("a"), Value::Int(2)])
+        );
+        assert_eq!(
+            Ordering::Equal,
+            compare_values(&[text("a"), Value::Int(1)], &[text("a"), Value::UInt(1)])
+        );
+        // values that can't be compared fall back to their text
+        assert_eq!(
+            Ordering::Less,
+            compare_values(&[text("1")], &[Value::Int(2)])
+        );
+    }
+
+    #[test]
+    fn test_frequency_format_counts() {
+        let counts = || create_counts(&[(1, 1), (2, 2), (3, 3), (4, 1)]);
+
+        assert_eq!(
+            vec![
+                vec!["f", "3", "3", "42.86", "42.86"],
+                vec!["f", "2", "2", "28.57", "71.43"],
+                vec!["f", "(other)", "2", "28.57", "100.00"],
+            ],
+            format_texts(format_counts(Some("f"), counts(), Order::Desc, Some(2)))
+        );
+        assert_eq!(
+            vec![
+                vec!["f", "1", "1", "14.29", "14.29"],
+                vec!["f", "2", "2", "28.57", "42.86"],
+                vec!["f", "3", "3", "42.86", "85.71"],
+                vec!["f", "4", "1", "14.29", "100.00"],
+            ],
+            format_texts(format_counts(Some("f"), counts(), Order::Value, Some(4)))
+        );
+        assert_eq!(
+            vec![vec!["f", "(other)", "7", "100.00", "100.00"]],
+            format_texts(format_counts(Some("f"), counts(), Order::Asc, Some(0)))
+        );
+    }
+
+    #[test]
+    fn test_frequency_compute_groups() {
+        let options = TextOptions::default();
+        let rows = vec![
+            Ok(vec![
+                Value::Str(String::from("br")),
+                Value::Bool(true),
+                Value::Int(1),
+            ]),
+            Ok(vec![
+                Value::Str(String::from("br")),
+                Value::Bool(true),
+                Value::Null,
+            ]),
+            Ok(vec![
+                Value::Str(String::from("br")),
+                Value::Bool(false),
+                Value::Int(3),
+            ]),
+            Ok(vec![
+                Value::Str(String::from("us")),
+                Value::Bool(true),
+                Value::Double(1.5),
+            ]),
+        ];
+        let mut counts = compute_groups(2, true, rows.into_iter(), &options).unwrap();
+
+        sort_counts(&mut counts, Order::Value);
+
+        assert_eq!(
+            vec![
+                Count {
+                    values: vec![Value::Str(String::from("br")), Value::Bool(false)],
+                    count: 1,
+                    sum: Some(3.0),
+                },
+                Count {
+                    values: vec![Value::Str(String::from("br")), Value::Bool(true)],
+                    count: 2,
+                    sum: Some(1.0),
+                },
+                Count {
+                    values: vec![Value::Str(String::from("us")), Value::Bool(true)],
+                    count: 1,
+                    sum: Some(1.5),
+                },
+            ],
+            counts
+        );
+    }
+
+    #[test]
+    fn test_frequency_format_groups() {
+        let counts = vec![
+            Count {
+                values: vec![Value::Str(String::from("br")), Value::Bool(true)],
+                count: 3,
+                sum: Some(10.0),
+            },
+            Count {
+                values: vec![Value::Str(String::from("br")), Value::Bool(false)],
+                count: 2,
+                sum: Some(2.5),
+            },
+            Count {
+                values: vec![Value::Str(String::from("us")), Value::Bool(true)],
+                count: 1,
+                sum: Some(1.0),
+            },
+        ];
+
+        assert_eq!(
+            vec![
+                vec!["br", "true", "3", "50.00", "50.00", "10.0"],
+                vec!["(other)", "", "3", "50.00", "100.00", "3.5"],
+            ],
+            format_texts(format_counts(None, counts, Order::Desc, Some(1)))
+        );
+    }
+
+    #[test]
+    fn test_frequency_order_try_from() {
+        assert_eq!(Ok(Order::Desc), Order::try_from("desc"));
+        assert_eq!(Ok(Order::Asc), Order::try_from("ASC"));
+        assert_eq!(Ok(Order::Value), Order::try_from("value"));
+        assert_eq!(
+            Err(Error::InvalidArgument(String::from("count"))),
+            Order::try_from("count")
+        );
+    }
+
     #[test]
     fn test_simple_messages_frequency() {
         let mut output = Cursor::new(Vec::new());
@@ -212,23 +743,21 @@ mod tests {
         let vec = output.into_inner();
         let actual = str::from_utf8(&vec).unwrap();
 
-        assert_eq!(8, actual.lines().count());
+        assert_eq!(12, actual.lines().count());
         assert!(actual.contains(
             &vec![
                 "",
-                "FIELD:  field_boolean",
-                "VALUE:  true", // 4 true
-                "COUNT:  4",
-                ""
-            ]
-            .join("\n")
-        ));
-        assert!(actual.contains(
-            &vec![
+                "FIELD:       field_boolean",
+                "VALUE:       false",
+                "COUNT:       5",
+                "PERCENT:     55.56",
+                "CUMULATIVE:  55.56",
                 "",
-                "FIELD:  field_boolean",
-                "VALUE:  false",
-                "COUNT:  5",
+                "FIELD:       field_boolean",
+                "VALUE:       true", // 4 true
+                "COUNT:       4",
+                "PERCENT:     44.44",
+                "CUMULATIVE:  100.00",
                 ""
             ]
             .join("\n")
@@ -267,4 +796,170 @@ mod tests {
         )));
         assert!(actual.ends_with(""));
     }
+
+    #[test]
+    fn test_simple_messages_frequency_top() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(5);
+        let arg_vec = vec![
+            "frequency",
+            path_str,
+            "-f=csv",
+            "-c=field_int32,field_boolean",
+            "--top=2",
+            "--order=value",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(
+            vec![
+                "FIELD,VALUE,COUNT,PERCENT,CUMULATIVE",
+                "field_int32,1,1,20.00,20.00",
+                "field_int32,2,1,20.00,40.00",
+                "field_int32,(other),3,60.00,100.00",
+                "field_boolean,false,3,60.00,60.00",
+                "field_boolean,true,2,40.00,100.00",
+            ],
+            actual.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_simple_messages_frequency_group_by() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(5);
+        let arg_vec = vec![
+            "frequency",
+            path_str,
+            "-f=csv",
+            "--group-by=field_boolean",
+            "--sum=field_int64",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(
+            vec![
+                "field_boolean,COUNT,PERCENT,CUMULATIVE,SUM",
+                "false,3,60.00,60.00,99.0",
+                "true,2,40.00,100.00,66.0",
+            ],
+            actual.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_simple_messages_frequency_json_format() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(5);
+        let arg_vec = vec![
+            "frequency",
+            path_str,
+            "-f=json",
+            "--group-by=field_boolean",
+            "--sum=field_int64",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(
+            vec![
+                "{\"field_boolean\":false,\"COUNT\":3,\"PERCENT\":60.00,\"CUMULATIVE\":60.00,\"SUM\":99.0}",
+                "{\"field_boolean\":true,\"COUNT\":2,\"PERCENT\":40.00,\"CUMULATIVE\":100.00,\"SUM\":66.0}",
+            ],
+            actual.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_simple_messages_frequency_sum_not_numeric() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(2);
+        let sum = |column: &str| {
+            let sum_arg = format!("--sum={}", column);
+            let arg_vec =
+                vec!["frequency", path_str, "--group-by=field_boolean", &sum_arg];
+            let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+            run(&args, &mut Cursor::new(Vec::new()))
+        };
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(Ok(()), sum("field_float"));
+        assert_eq!(
+            Err(Error::InvalidArgument(String::from(
+                "--sum column 'field_string' of type UTF8 is not numeric"
+            ))),
+            sum("field_string")
+        );
+        assert_eq!(
+            Err(Error::InvalidArgument(String::from(
+                "--sum column 'field_timestamp' of type INT96 is not numeric"
+            ))),
+            sum("field_timestamp")
+        );
+        assert_eq!(
+            Err(Error::UnknownColumn(
+                String::from("field_str"),
+                vec![String::from("field_string")]
+            )),
+            sum("field_str")
+        );
+    }
+
+    #[test]
+    fn test_frequency_top_args() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let zero = vec!["frequency", path_str, "--top=0"];
+        let one = vec!["frequency", path_str, "--top=1"];
+
+        assert_eq!(true, def().get_matches_from_safe(zero).is_err());
+        assert_eq!(true, def().get_matches_from_safe(one).is_ok());
+    }
+
+    #[test]
+    fn test_frequency_group_by_args() {
+        let conflict = vec!["frequency", "file.parquet", "-g=a", "-c=b"];
+        let sum_only = vec!["frequency", "file.parquet", "--sum=a"];
+
+        assert_eq!(true, def().get_matches_from_safe(conflict).is_err());
+        assert_eq!(true, def().get_matches_from_safe(sum_only).is_err());
+    }
 }
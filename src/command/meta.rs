@@ -0,0 +1,327 @@
+use crate::api::{Error, Result};
+use crate::command::args;
+use crate::output::{OutputFormat, OutputWriter};
+use crate::reader::ParquetFile;
+use crate::value::{Value, JULIAN_DAY_OF_EPOCH, MILLIS_PER_DAY};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use parquet::basic::LogicalType;
+use parquet::data_type::Int96;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::reader::FileReader;
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::ColumnDescriptor;
+use std::io::Write;
+
+pub fn def() -> App<'static, 'static> {
+    SubCommand::with_name("meta")
+        .about("Show file, row group and column chunk metadata")
+        .arg(
+            Arg::with_name("format")
+                .help("Output format")
+                .possible_values(&OutputFormat::values())
+                .default_value("table")
+                .long("format")
+                .short("f"),
+        )
+        .args(&args::csv_args())
+        .args(&args::table_args())
+        .arg(
+            Arg::with_name("path")
+                .validator(args::validate_path)
+                .help("Path to parquet")
+                .required(true)
+                .index(1),
+        )
+}
+
+#[inline]
+fn int32_value(value: i32, descr: &ColumnDescriptor) -> Value {
+    match descr.logical_type() {
+        LogicalType::UINT_8 | LogicalType::UINT_16 | LogicalType::UINT_32 => {
+            Value::UInt(u64::from(value as u32))
+        }
+        LogicalType::DATE => Value::Date(value as u32),
+        LogicalType::DECIMAL => Value::Decimal(i128::from(value), descr.type_scale()),
+        _ => Value::Int(i64::from(value)),
+    }
+}
+
+#[inline]
+fn int64_value(value: i64, descr: &ColumnDescriptor) -> Value {
+    match descr.logical_type() {
+        LogicalType::UINT_64 => Value::UInt(value as u64),
+        LogicalType::TIMESTAMP_MILLIS => Value::TimestampMillis(value as u64),
+        LogicalType::TIMESTAMP_MICROS => Value::TimestampMicros(value as u64),
+        LogicalType::DECIMAL => Value::Decimal(i128::from(value), descr.type_scale()),
+        _ => Value::Int(value),
+    }
+}
+
+/// Converts an impala timestamp, nanoseconds of the day and julian day, into millis.
+#[inline]
+fn int96_value(value: &Int96) -> Value {
+    let data = value.data();
+    let nanos = (u64::from(data[1]) << 32) | u64::from(data[0]);
+    let days = i64::from(data[2]) - JULIAN_DAY_OF_EPOCH;
+    let millis = days * MILLIS_PER_DAY + (nanos / 1_000_000) as i64;
+
+    Value::TimestampMillis(millis as u64)
+}
+
+#[inline]
+fn bytes_value(data: &[u8], descr: &ColumnDescriptor) -> Value {
+    match descr.logical_type() {
+        LogicalType::UTF8 | LogicalType::ENUM | LogicalType::JSON => {
+            Value::Str(String::from_utf8_lossy(data).into_owned())
+        }
+        _ => Value::Bytes(data.to_vec()),
+    }
+}
+
+/// Converts the min/max statistics of a column chunk into values.
+///
+/// Statistics are shown as stored, byte arrays may not be ordered lexicographically
+/// depending on the writer.
+fn statistics_range(column: &ColumnChunkMetaData) -> Option<(Value, Value)> {
+    let descr = column.column_descr();
+    let stats = column.statistics().filter(|s| s.has_min_max_set())?;
+    let range = match stats {
+        Statistics::Boolean(s) => (Value::Bool(*s.min()), Value::Bool(*s.max())),
+        Statistics::Int32(s) => {
+            (int32_value(*s.min(), descr), int32_value(*s.max(), descr))
+        }
+        Statistics::Int64(s) => {
+            (int64_value(*s.min(), descr), int64_value(*s.max(), descr))
+        }
+        Statistics::Int96(s) => (int96_value(s.min()), int96_value(s.max())),
+        Statistics::Float(s) => (Value::Float(*s.min()), Value::Float(*s.max())),
+        Statistics::Double(s) => (Value::Double(*s.min()), Value::Double(*s.max())),
+        Statistics::ByteArray(s) => (
+            bytes_value(s.min().data(), descr),
+            bytes_value(s.max().data(), descr),
+        ),
+        Statistics::FixedLenByteArray(s) => (
+            bytes_value(s.min().data(), descr),
+            bytes_value(s.max().data(), descr),
+        ),
+    };
+
+    Some(range)
+}
+
+#[inline]
+fn format_row(
+    path: &str,
+    row_group: &str,
+    column: &str,
+    key: &str,
+    value: String,
+) -> Vec<String> {
+    vec![
+        path.to_string(),
+        row_group.to_string(),
+        column.to_string(),
+        key.to_string(),
+        value,
+    ]
+}
+
+fn format_column(
+    path: &str,
+    row_group: &str,
+    column: &ColumnChunkMetaData,
+) -> Vec<Vec<String>> {
+    let name = column.column_path().string();
+    let encodings = column
+        .encodings()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let mut values = vec![
+        ("type", column.column_type().to_string()),
+        ("compression", column.compression().to_string()),
+        ("encodings", encodings.join(" ")),
+        ("num_values", column.num_values().to_string()),
+        ("compressed_size", column.compressed_size().to_string()),
+        ("uncompressed_size", column.uncompressed_size().to_string()),
+        ("file_offset", column.file_offset().to_string()),
+        ("data_page_offset", column.data_page_offset().to_string()),
+    ];
+
+    if let Some(offset) = column.dictionary_page_offset() {
+        values.push(("dictionary_page_offset", offset.to_string()));
+    }
+
+    if let Some(offset) = column.index_page_offset() {
+        values.push(("index_page_offset", offset.to_string()));
+    }
+
+    if let Some(stats) = column.statistics() {
+        values.push(("null_count", stats.null_count().to_string()));
+
+        if let Some(count) = stats.distinct_count() {
+            values.push(("distinct_count", count.to_string()));
+        }
+    }
+
+    if let Some((min, max)) = statistics_range(column) {
+        values.push(("min", min.to_string()));
+        values.push(("max", max.to_string()));
+    }
+
+    values
+        .into_iter()
+        .map(|t| format_row(path, row_group, &name, t.0, t.1))
+        .collect()
+}
+
+fn format_metadata(path: &str, metadata: &ParquetMetaData) -> Vec<Vec<String>> {
+    let file = metadata.file_metadata();
+    let created_by = file.created_by().clone().unwrap_or_default();
+    let mut rows = vec![
+        format_row(path, "", "", "created_by", created_by),
+        format_row(path, "", "", "version", file.version().to_string()),
+        format_row(path, "", "", "num_rows", file.num_rows().to_string()),
+        format_row(
+            path,
+            "",
+            "",
+            "num_row_groups",
+            metadata.num_row_groups().to_string(),
+        ),
+    ];
+
+    for entry in file.key_value_metadata().iter().flatten() {
+        let key = format!("key_value.{}", entry.key);
+        let value = entry.value.clone().unwrap_or_default();
+
+        rows.push(format_row(path, "", "", &key, value));
+    }
+
+    for (i, row_group) in metadata.row_groups().iter().enumerate() {
+        let index = i.to_string();
+
+        rows.push(format_row(
+            path,
+            &index,
+            "",
+            "num_rows",
+            row_group.num_rows().to_string(),
+        ));
+        rows.push(format_row(
+            path,
+            &index,
+            "",
+            "total_byte_size",
+            row_group.total_byte_size().to_string(),
+        ));
+
+        for column in row_group.columns() {
+            rows.extend(format_column(path, &index, column));
+        }
+    }
+
+    rows
+}
+
+pub fn run<W: Write>(matches: &ArgMatches, out: &mut W) -> Result<()> {
+    let format = args::output_format_value(matches, "format")?;
+    let path = args::path_value(matches, "path")?;
+    let parquet = ParquetFile::from(path);
+    let mut rows = Vec::new();
+
+    for reader in parquet.readers() {
+        let (file, reader) = reader?;
+        let name = file.to_string_lossy();
+
+        rows.extend(format_metadata(&name, reader.metadata()));
+    }
+
+    if rows.is_empty() {
+        return Err(Error::from(path.to_path_buf()));
+    }
+
+    let headers = vec![
+        String::from("FILE"),
+        String::from("ROW_GROUP"),
+        String::from("COLUMN"),
+        String::from("KEY"),
+        String::from("VALUE"),
+    ];
+    let iter = rows.into_iter().map(Ok);
+    let csv_options = args::csv_options_value(matches)?;
+    let table_options = args::table_options_value(matches)?;
+    let mut writer = OutputWriter::new(headers, iter)
+        .format(format)
+        .csv_options(csv_options)
+        .table_options(table_options);
+
+    writer.write(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+    use std::io::Cursor;
+    use std::str;
+
+    #[test]
+    fn test_meta_simple_messages() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(2);
+        let arg_vec = vec!["meta", path_str, "-f=csv"];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let lines = actual.lines().collect::<Vec<_>>();
+        let expected = vec![
+            format!("{},,,version,1", path_str),
+            format!("{},,,num_rows,2", path_str),
+            format!("{},,,num_row_groups,1", path_str),
+            format!("{},0,,num_rows,2", path_str),
+            format!("{},0,field_int32,type,INT32", path_str),
+            format!("{},0,field_int32,num_values,2", path_str),
+            format!("{},0,field_int32,null_count,0", path_str),
+            format!("{},0,field_int32,min,1", path_str),
+            format!("{},0,field_int32,max,2", path_str),
+            // parquet-rs orders byte array statistics by length first
+            format!("{},0,field_string,min,\"\"\"odd 11111\"\"\"", path_str),
+            format!("{},0,field_string,max,\"\"\"even 22222\"\"\"", path_str),
+        ];
+
+        assert_eq!(lines[0], "FILE,ROW_GROUP,COLUMN,KEY,VALUE");
+
+        for line in expected {
+            assert_eq!(true, lines.contains(&line.as_str()), "missing {}", line);
+        }
+    }
+
+    #[test]
+    fn test_meta_int96_value() {
+        let mut value = Int96::new();
+
+        value.set_data(0, 0, 2_454_923);
+
+        assert_eq!(
+            Value::TimestampMillis(1_238_544_000_000),
+            int96_value(&value)
+        );
+
+        value.set_data(4_165_425_152, 13, 2_454_923);
+
+        assert_eq!(
+            Value::TimestampMillis(1_238_544_060_000),
+            int96_value(&value)
+        );
+    }
+}
@@ -2,6 +2,8 @@ pub(in crate::command) mod args;
 
 pub mod count;
 pub mod frequency;
+pub mod meta;
 pub mod read;
 pub mod sample;
 pub mod schema;
+pub mod stats;
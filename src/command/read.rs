@@ -1,7 +1,8 @@
 use crate::api::Result;
 use crate::command::args;
-use crate::output::{OutputFormat, OutputWriter};
+use crate::output::OutputWriter;
 use crate::reader::ParquetFile;
+use crate::sink;
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::io::Write;
 
@@ -25,6 +26,14 @@ pub fn def() -> App<'static, 'static> {
                 .multiple(true)
                 .short("s"),
         )
+        .arg(
+            Arg::with_name("where")
+                .validator(args::validate_expression)
+                .help("Filter rows using an expression")
+                .takes_value(true)
+                .long("where")
+                .short("w"),
+        )
         .arg(
             Arg::with_name("limit")
                 .validator(args::validate_number)
@@ -33,14 +42,41 @@ pub fn def() -> App<'static, 'static> {
                 .long("limit")
                 .short("l"),
         )
+        .arg(
+            Arg::with_name("verbose")
+                .help("Show row groups skipped using statistics")
+                .long("verbose"),
+        )
         .arg(
             Arg::with_name("format")
                 .help("Output format")
-                .possible_values(&OutputFormat::values())
+                .possible_values(&sink::format_values())
                 .default_value("table")
                 .long("format")
                 .short("f"),
         )
+        .args(&args::csv_args())
+        .args(&args::table_args())
+        .args(&args::text_args())
+        .arg(
+            Arg::with_name("types")
+                .help("Show the type of each column in the headers")
+                .long("types"),
+        )
+        .arg(
+            Arg::with_name("compression")
+                .help("Compression codec of the parquet format")
+                .possible_values(&sink::compression_values())
+                .default_value("snappy")
+                .long("compression"),
+        )
+        .arg(
+            Arg::with_name("row-group-size")
+                .validator(args::validate_number)
+                .help("Max number of rows per row group of the parquet format")
+                .default_value("100000")
+                .long("row-group-size"),
+        )
         .arg(
             Arg::with_name("path")
                 .validator(args::validate_path)
@@ -51,29 +87,63 @@ pub fn def() -> App<'static, 'static> {
 }
 
 pub fn run<W: Write>(matches: &ArgMatches, out: &mut W) -> Result<()> {
-    let format = args::output_format_value(matches, "format")?;
     let columns = args::string_values(matches, "columns")?;
     let search = args::filter_values(matches, "search")?;
+    let expression = args::expression_value(matches, "where")?;
     let limit = args::usize_value(matches, "limit")?;
     let path = args::path_value(matches, "path")?;
     let parquet = ParquetFile::from(path)
         .with_fields(columns)
-        .with_filters(search);
+        .with_filters(search)
+        .with_expression(expression);
 
-    let headers = parquet.field_names()?;
     let iter = parquet.iter().take(limit);
-    let mut writer = OutputWriter::new(headers, iter).format(format);
 
-    writer.write(out)
+    if let Some(sink) = args::parquet_sink_value(matches)? {
+        sink.write(parquet.projected_schema()?, iter)?;
+    } else {
+        let format = args::output_format_value(matches, "format")?;
+        let headers = parquet.field_names()?;
+        let csv_options = args::csv_options_value(matches)?;
+        let table_options = args::table_options_value(matches)?;
+        let text_options = args::text_options_value(matches)?;
+        let types = if matches.is_present("types") {
+            Some(parquet.field_types()?)
+        } else {
+            None
+        };
+        let mut writer = OutputWriter::new(headers, iter)
+            .format(format)
+            .csv_options(csv_options)
+            .table_options(table_options)
+            .text_options(text_options)
+            .types(types);
+
+        writer.write(out)?;
+    }
+
+    if matches.is_present("verbose") {
+        let (skipped, total) = parquet.skipped_row_groups();
+
+        eprintln!("Skipped {} of {} row groups", skipped, total);
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api;
+    use crate::api::{self, Error};
+    use crate::value::Value;
     use std::io::Cursor;
     use std::str;
 
+    // global in the application
+    fn output_arg() -> Arg<'static, 'static> {
+        Arg::with_name("output").takes_value(true).short("o")
+    }
+
     #[test]
     fn test_read_simple_messages() {
         let mut output = Cursor::new(Vec::new());
@@ -138,6 +208,163 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_read_simple_messages_with_filters_not_selected() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+        let expected = vec!["field_int32", "2", ""].join("\n");
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(3);
+        let args = subcomand
+            .get_matches_from_safe(vec![
+                "read",
+                path_str,
+                "-s=field_string:even",
+                "-c=field_int32",
+            ])
+            .unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_where() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+        let expected = vec!["field_int32", "2", "3", "4", ""].join("\n");
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(4);
+        let args = subcomand
+            .get_matches_from_safe(vec![
+                "read",
+                path_str,
+                "-w=field_int64 >= 22 AND (field_boolean = true OR field_string ~ '3$')",
+                "-c=field_int32",
+            ])
+            .unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_where_err() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(2);
+        let args = subcomand
+            .get_matches_from_safe(vec!["read", path_str, "-w=field_string > 1"])
+            .unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(
+            Err(Error::Filter(String::from(
+                "Invalid value 1 for column 'field_string' of type UTF8"
+            ))),
+            run(&args, &mut output)
+        );
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_format_parquet() {
+        let mut output = Cursor::new(Vec::new());
+        let dir = api::tests::temp_dir();
+        let source = dir.path().join("source.parquet");
+        let target = dir.path().join("target.parquet");
+        let source_str = source.to_str().unwrap();
+        let target_str = target.to_str().unwrap();
+
+        let subcomand = def().arg(output_arg());
+        let msgs = api::tests::create_simple_messages(5);
+        let arg_vec = vec![
+            "read",
+            source_str,
+            "-f=parquet",
+            "-o",
+            target_str,
+            "-c=field_int32,field_string",
+            "-w=field_boolean = false",
+            "--compression=zstd",
+        ];
+
+        api::tests::write_simple_messages_parquet(&source, &msgs);
+
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+        assert_eq!(true, output.into_inner().is_empty());
+
+        let rows = ParquetFile::from(target.as_path())
+            .iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                vec![Value::Int(1), Value::Str(String::from("odd 11111"))],
+                vec![Value::Int(3), Value::Str(String::from("odd 33333"))],
+                vec![Value::Int(5), Value::Str(String::from("odd 55555"))],
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_format_parquet_without_output() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let arg_vec = vec!["read", path_str, "-f=parquet"];
+        let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+        assert_eq!(
+            Err(api::Error::InvalidArgument(String::from("output"))),
+            run(&args, &mut Cursor::new(Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_format_parquet_existing_output() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let target = api::tests::temp_file("target", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let target_str = target.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(2);
+        let subcomand = def().arg(output_arg());
+        let arg_vec = vec!["read", path_str, "-f=parquet", "-o", target_str];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(
+            Err(api::Error::FileExists(target.path().to_path_buf())),
+            run(&args, &mut Cursor::new(Vec::new()))
+        );
+    }
+
     #[test]
     fn test_read_simple_messages_with_format_vertical() {
         let mut output = Cursor::new(Vec::new());
@@ -188,7 +415,66 @@ mod tests {
 
         let vec = output.into_inner();
         let actual = str::from_utf8(&vec).unwrap();
-        let expected = "field_int32,field_string\n1,\"odd 11111\"\n2,\"even 22222\"\n";
+        let expected = "field_int32,field_string\n1,odd 11111\n2,even 22222\n";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_types() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(1);
+        let arg_vec = vec![
+            "read",
+            path_str,
+            "-f=csv",
+            "--types",
+            "-c=field_int32,field_string,field_timestamp",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let lines = actual.lines().collect::<Vec<_>>();
+
+        assert_eq!(3, lines.len());
+        assert_eq!("field_int32,field_string,field_timestamp", lines[0]);
+        assert_eq!("INT32,UTF8,INT96", lines[1]);
+    }
+
+    #[test]
+    fn test_read_simple_messages_with_format_json() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(2);
+        let arg_vec = vec!["read", path_str, "-f=json", "-c=field_int32,field_int64"];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = vec![
+            "{\"field_int32\":1,\"field_int64\":11}",
+            "{\"field_int32\":2,\"field_int64\":22}",
+            "",
+        ]
+        .join("\n");
 
         assert_eq!(actual, expected);
     }
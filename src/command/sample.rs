@@ -1,12 +1,17 @@
 use crate::api::Result;
 use crate::command::args;
-use crate::output::{OutputFormat, OutputWriter};
-use crate::reader::ParquetFile;
+use crate::output::OutputWriter;
+use crate::path;
+use crate::reader::{self, ParquetFile};
+use crate::sink;
+use crate::value::Value;
 use clap::{App, Arg, ArgMatches, SubCommand};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::collections::HashSet;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::Write;
+use std::path::PathBuf;
 
 pub fn def() -> App<'static, 'static> {
     SubCommand::with_name("sample")
@@ -19,6 +24,22 @@ pub fn def() -> App<'static, 'static> {
                 .multiple(true)
                 .short("c"),
         )
+        .arg(
+            Arg::with_name("search")
+                .validator(args::validate_filter)
+                .help("Search columns")
+                .takes_value(true)
+                .long("search")
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("where")
+                .validator(args::validate_expression)
+                .help("Filter rows using an expression")
+                .takes_value(true)
+                .long("where")
+                .short("w"),
+        )
         .arg(
             Arg::with_name("sample")
                 .validator(args::validate_number)
@@ -27,14 +48,77 @@ pub fn def() -> App<'static, 'static> {
                 .long("sample")
                 .short("s"),
         )
+        .arg(
+            Arg::with_name("fraction")
+                .validator(args::validate_fraction)
+                .help("Keep each row with the given probability, instead of a fixed sample size")
+                .takes_value(true)
+                .conflicts_with("stratify-by")
+                .long("fraction"),
+        )
+        .arg(
+            Arg::with_name("row-groups")
+                .validator(args::validate_number)
+                .help("Only sample the rows of a number of random row groups, chosen using the file metadata")
+                .takes_value(true)
+                .long("row-groups"),
+        )
+        .arg(
+            Arg::with_name("stratify-by")
+                .help("Sample each distinct value of a column separately")
+                .takes_value(true)
+                .long("stratify-by"),
+        )
+        .arg(
+            Arg::with_name("per-group")
+                .validator(args::validate_number)
+                .help("Sample size of each group, instead of splitting the sample size proportionally")
+                .takes_value(true)
+                .requires("stratify-by")
+                .long("per-group"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .help("Show the number of rows sampled from each group of --stratify-by")
+                .long("verbose"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .validator(args::validate_seed)
+                .help("Seed of the random generator, the same seed gives the same sample")
+                .takes_value(true)
+                .long("seed"),
+        )
         .arg(
             Arg::with_name("format")
                 .help("Output format")
-                .possible_values(&OutputFormat::values())
+                .possible_values(&sink::format_values())
                 .default_value("table")
                 .long("format")
                 .short("f"),
         )
+        .args(&args::csv_args())
+        .args(&args::table_args())
+        .args(&args::text_args())
+        .arg(
+            Arg::with_name("types")
+                .help("Show the type of each column in the headers")
+                .long("types"),
+        )
+        .arg(
+            Arg::with_name("compression")
+                .help("Compression codec of the parquet format")
+                .possible_values(&sink::compression_values())
+                .default_value("snappy")
+                .long("compression"),
+        )
+        .arg(
+            Arg::with_name("row-group-size")
+                .validator(args::validate_number)
+                .help("Max number of rows per row group of the parquet format")
+                .default_value("100000")
+                .long("row-group-size"),
+        )
         .arg(
             Arg::with_name("path")
                 .validator(args::validate_path)
@@ -44,32 +128,300 @@ pub fn def() -> App<'static, 'static> {
         )
 }
 
-fn sample_indexes(sample: usize, size: usize) -> HashSet<usize> {
-    let mut vec = (0..size).collect::<Vec<_>>();
-    let mut rng = thread_rng();
+/// Uniform random sample of a stream of unknown length, keeping the position of each
+/// selected item.
+///
+/// Uses Algorithm L, once the reservoir is full the number of items to skip before the
+/// next replacement is computed up front instead of drawing a number for each item.
+struct Reservoir<T> {
+    size: usize,
+    items: Vec<(usize, T)>,
+    count: usize,
+    next: usize,
+    weight: f64,
+}
 
-    vec.shuffle(&mut rng);
+impl<T> Reservoir<T> {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            items: Vec::new(),
+            count: 0,
+            next: 0,
+            weight: 0.0,
+        }
+    }
+
+    // uniform in (0, 1], avoids the logarithm of zero
+    #[inline]
+    fn random<R: Rng>(rng: &mut R) -> f64 {
+        1.0 - rng.gen::<f64>()
+    }
+
+    fn skip<R: Rng>(&mut self, rng: &mut R) {
+        self.weight *= (Self::random(rng).ln() / self.size as f64).exp();
+
+        let skip = (Self::random(rng).ln() / (1.0 - self.weight).ln()).floor();
+
+        self.next = self.count.saturating_add(skip as usize);
+    }
+
+    fn add<R: Rng>(&mut self, item: T, rng: &mut R) {
+        let index = self.count;
+
+        self.count += 1;
+
+        if self.items.len() < self.size {
+            self.items.push((index, item));
+
+            if self.items.len() == self.size {
+                self.weight = 1.0;
+                self.skip(rng);
+            }
+
+            return;
+        }
+
+        if self.size > 0 && index == self.next {
+            self.items[rng.gen_range(0, self.size)] = (index, item);
+            self.skip(rng);
+        }
+    }
+
+    /// Drops random items until at most `size` are left, keeping the sample uniform.
+    fn shrink<R: Rng>(&mut self, size: usize, rng: &mut R) {
+        while self.items.len() > size {
+            self.items.swap_remove(rng.gen_range(0, self.items.len()));
+        }
+    }
 
-    vec.iter().take(sample).cloned().collect()
+    /// Selected items in the order they were added.
+    fn into_vec(mut self) -> Vec<T> {
+        self.items.sort_by_key(|t| t.0);
+        self.items.into_iter().map(|t| t.1).collect()
+    }
+}
+
+/// Picks random row groups of the parquet files, using only their metadata.
+fn sample_row_groups<R: Rng>(
+    parquet: &ParquetFile,
+    size: usize,
+    rng: &mut R,
+) -> Result<HashMap<PathBuf, HashSet<usize>>> {
+    let mut reservoir = Reservoir::new(size);
+    let mut selection = HashMap::new();
+
+    for row_group in parquet.row_groups() {
+        reservoir.add(row_group?, rng);
+    }
+
+    for (path, index) in reservoir.into_vec() {
+        selection
+            .entry(path)
+            .or_insert_with(HashSet::new)
+            .insert(index);
+    }
+
+    Ok(selection)
+}
+
+/// Splits the sample size between groups proportionally to their number of rows,
+/// using the largest remainder method.
+fn allocate<R: Rng>(size: usize, counts: &[usize], rng: &mut R) -> Vec<usize> {
+    let total = counts.iter().sum::<usize>() as u128;
+
+    if total <= size as u128 {
+        return counts.to_vec();
+    }
+
+    let quotas = counts
+        .iter()
+        .map(|c| size as u128 * *c as u128)
+        .collect::<Vec<_>>();
+    let mut vec = quotas
+        .iter()
+        .map(|q| (q / total) as usize)
+        .collect::<Vec<_>>();
+    let mut remainders = (0..counts.len()).collect::<Vec<_>>();
+    let remaining = size - vec.iter().sum::<usize>();
+
+    // shuffled before the stable sort, so ties go to random groups
+    remainders.shuffle(rng);
+    remainders.sort_by_key(|i| std::cmp::Reverse(quotas[*i] % total));
+
+    for i in remainders.into_iter().take(remaining) {
+        vec[i] += 1;
+    }
+
+    vec
+}
+
+/// Sampled rows of each group, along with their position so groups can be merged back
+/// in order.
+type Strata = BTreeMap<String, Reservoir<(usize, Vec<Value>)>>;
+
+/// Samples the rows of each distinct value of a column, keyed by the formatted value.
+fn stratify<I, R>(iter: I, column: usize, size: usize, rng: &mut R) -> Result<Strata>
+where
+    I: Iterator<Item = Result<Vec<Value>>>,
+    R: Rng,
+{
+    let mut strata = BTreeMap::new();
+
+    for (index, row) in iter.enumerate() {
+        let row = row?;
+        let key = row[column].to_string();
+
+        strata
+            .entry(key)
+            .or_insert_with(|| Reservoir::new(size))
+            .add((index, row), rng);
+    }
+
+    Ok(strata)
+}
+
+/// Samples each distinct value of the `name` column.
+///
+/// The column is resolved against the whole schema and read along with the selected
+/// columns, so it doesn't need to be selected. With `verbose` the number of rows
+/// selected from each group is printed.
+fn stratified_sample<R: Rng>(
+    parquet: &ParquetFile,
+    name: &str,
+    sample: usize,
+    per_group: Option<usize>,
+    verbose: bool,
+    rng: &mut R,
+) -> Result<Vec<Vec<Value>>> {
+    let schema = parquet.schema()?;
+    let column = path::resolve(&schema, name)
+        .ok_or_else(|| reader::unknown_column(name, &path::names(&schema)))?;
+    let mut fields = parquet.field_names()?;
+    let num_fields = fields.len();
+
+    fields.push(column.name);
+
+    let parquet = parquet.clone().with_fields(Some(fields));
+    let size = per_group.unwrap_or(sample);
+    let mut strata = stratify(parquet.iter(), num_fields, size, rng)?;
+
+    if per_group.is_none() {
+        let counts = strata.values().map(|r| r.count).collect::<Vec<_>>();
+
+        for (reservoir, size) in strata.values_mut().zip(allocate(sample, &counts, rng)) {
+            reservoir.shrink(size, rng);
+        }
+    }
+
+    let mut rows = Vec::new();
+
+    for (key, reservoir) in strata {
+        if verbose {
+            eprintln!(
+                "{}: {} of {} rows",
+                key,
+                reservoir.items.len(),
+                reservoir.count
+            );
+        }
+
+        rows.extend(reservoir.into_vec());
+    }
+
+    rows.sort_by_key(|t| t.0);
+
+    // drop the column only read to stratify
+    Ok(rows
+        .into_iter()
+        .map(|mut t| {
+            t.1.truncate(num_fields);
+            t.1
+        })
+        .collect())
 }
 
 pub fn run<W: Write>(matches: &ArgMatches, out: &mut W) -> Result<()> {
-    let format = args::output_format_value(matches, "format")?;
     let columns = args::string_values(matches, "columns")?;
+    let search = args::filter_values(matches, "search")?;
     let sample = args::usize_value(matches, "sample")?;
+    let expression = args::expression_value(matches, "where")?;
+    let seed = args::seed_value(matches, "seed")?;
+    let fraction = args::fraction_value(matches, "fraction")?;
+    let per_group = match matches.value_of("per-group") {
+        Some(_) => Some(args::usize_value(matches, "per-group")?),
+        None => None,
+    };
+    let row_groups = match matches.value_of("row-groups") {
+        Some(_) => Some(args::usize_value(matches, "row-groups")?),
+        None => None,
+    };
     let path = args::path_value(matches, "path")?;
-    let parquet = ParquetFile::from((path, columns));
-    let headers = parquet.field_names()?;
-    let size = parquet.num_rows();
-
-    let rows = parquet.iter();
-    let indexes = sample_indexes(sample, size);
-    let iter = rows
-        .enumerate()
-        .filter(|t| indexes.contains(&t.0))
-        .map(|r| r.1);
+    let parquet = ParquetFile::from(path)
+        .with_fields(columns)
+        .with_filters(search)
+        .with_expression(expression);
+
+    // explicit algorithm, samples must not change with the rand version
+    let mut rng = ChaCha20Rng::seed_from_u64(seed.unwrap_or_else(|| thread_rng().gen()));
+    let parquet = match row_groups {
+        Some(size) => {
+            let selection = sample_row_groups(&parquet, size, &mut rng)?;
+
+            parquet.with_row_groups(Some(selection))
+        }
+        None => parquet,
+    };
+
+    let iter: Box<dyn Iterator<Item = Result<Vec<Value>>>> =
+        match (matches.value_of("stratify-by"), fraction) {
+            (Some(name), _) => {
+                let verbose = matches.is_present("verbose");
+                let rows = stratified_sample(
+                    &parquet, name, sample, per_group, verbose, &mut rng,
+                )?;
+
+                Box::new(rows.into_iter().map(Ok))
+            }
+            // streamed, the sample size isn't bounded
+            (None, Some(fraction)) => Box::new(
+                parquet
+                    .iter()
+                    .filter(move |r| r.is_err() || rng.gen::<f64>() < fraction),
+            ),
+            (None, None) => {
+                let mut reservoir = Reservoir::new(sample);
+
+                for row in parquet.iter() {
+                    reservoir.add(row?, &mut rng);
+                }
+
+                Box::new(reservoir.into_vec().into_iter().map(Ok))
+            }
+        };
+
+    if let Some(sink) = args::parquet_sink_value(matches)? {
+        sink.write(parquet.projected_schema()?, iter)?;
+
+        return Ok(());
+    }
 
-    let mut writer = OutputWriter::new(headers, iter).format(format);
+    let format = args::output_format_value(matches, "format")?;
+    let headers = parquet.field_names()?;
+    let csv_options = args::csv_options_value(matches)?;
+    let table_options = args::table_options_value(matches)?;
+    let text_options = args::text_options_value(matches)?;
+    let types = if matches.is_present("types") {
+        Some(parquet.field_types()?)
+    } else {
+        None
+    };
+    let mut writer = OutputWriter::new(headers, iter)
+        .format(format)
+        .csv_options(csv_options)
+        .table_options(table_options)
+        .text_options(text_options)
+        .types(types);
 
     writer.write(out)
 }
@@ -82,6 +434,126 @@ mod tests {
     use std::io::Cursor;
     use std::str;
 
+    #[test]
+    fn test_sample_reservoir() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let mut reservoir = Reservoir::new(10);
+
+        for i in 0..10_000 {
+            reservoir.add(i, &mut rng);
+        }
+
+        let vec = reservoir.into_vec();
+        let mut sorted = vec.clone();
+
+        sorted.sort();
+        sorted.dedup();
+
+        assert_eq!(10, vec.len());
+        assert_eq!(sorted, vec);
+        assert_eq!(true, vec.iter().any(|i| *i >= 10));
+    }
+
+    #[test]
+    fn test_sample_reservoir_smaller_than_size() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let mut empty = Reservoir::new(0);
+        let mut reservoir = Reservoir::new(10);
+
+        for i in 0..5 {
+            empty.add(i, &mut rng);
+            reservoir.add(i, &mut rng);
+        }
+
+        assert_eq!(Vec::<i32>::new(), empty.into_vec());
+        assert_eq!(vec![0, 1, 2, 3, 4], reservoir.into_vec());
+    }
+
+    #[test]
+    fn test_sample_reservoir_uniform() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let mut counts = vec![0; 100];
+
+        for _ in 0..2_000 {
+            let mut reservoir = Reservoir::new(10);
+
+            for i in 0..100 {
+                reservoir.add(i, &mut rng);
+            }
+
+            for i in reservoir.into_vec() {
+                counts[i] += 1;
+            }
+        }
+
+        // each item is expected 200 times
+        assert_eq!(true, counts.iter().all(|c| *c > 120 && *c < 280));
+    }
+
+    #[test]
+    fn test_sample_reservoir_shrink() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let mut reservoir = Reservoir::new(10);
+
+        for i in 0..10 {
+            reservoir.add(i, &mut rng);
+        }
+
+        reservoir.shrink(20, &mut rng);
+
+        assert_eq!(10, reservoir.items.len());
+
+        reservoir.shrink(3, &mut rng);
+
+        assert_eq!(3, reservoir.items.len());
+        assert_eq!(10, reservoir.count);
+    }
+
+    #[test]
+    fn test_sample_allocate() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        assert_eq!(Vec::<usize>::new(), allocate(10, &[], &mut rng));
+        assert_eq!(vec![2, 3], allocate(10, &[2, 3], &mut rng));
+        assert_eq!(vec![5, 5], allocate(10, &[50, 50], &mut rng));
+        assert_eq!(vec![9, 1, 0], allocate(10, &[900, 60, 40], &mut rng));
+        assert_eq!(vec![0, 0], allocate(0, &[1, 1], &mut rng));
+
+        // ties go to random groups
+        let mut extra = HashSet::new();
+
+        for _ in 0..100 {
+            let vec = allocate(10, &[4, 4, 4], &mut rng);
+
+            assert_eq!(10, vec.iter().sum::<usize>());
+            extra.insert(vec.iter().position(|s| *s == 4).unwrap());
+        }
+
+        assert_eq!(3, extra.len());
+    }
+
+    #[test]
+    fn test_sample_stratify() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let rows = (0..100).map(|i| Ok(vec![Value::Int(i), Value::Bool(i % 10 == 0)]));
+        let strata = stratify(rows, 1, 5, &mut rng).unwrap();
+        let keys = strata.keys().cloned().collect::<Vec<_>>();
+        let counts = strata.values().map(|r| r.count).collect::<Vec<_>>();
+        let sizes = strata.values().map(|r| r.items.len()).collect::<Vec<_>>();
+
+        assert_eq!(vec!["false", "true"], keys);
+        assert_eq!(vec![90, 10], counts);
+        assert_eq!(vec![5, 5], sizes);
+        assert_eq!(
+            true,
+            strata["true"]
+                .items
+                .iter()
+                .map(|t| &t.1)
+                .all(|(i, row)| row[0] == Value::Int(*i as i64))
+        );
+    }
+
     #[test]
     fn test_sample_simple_messages() {
         let mut output = Cursor::new(Vec::new());
@@ -209,4 +681,265 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_sample_simple_messages_with_search() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let expected = vec!["field_int32", "1", "3", "5", ""].join("\n");
+
+        let subcomand = def();
+        let arg_vec = vec![
+            "sample",
+            path_str,
+            "-f=csv",
+            "-c=field_int32",
+            "--search=field_boolean:false",
+        ];
+
+        let msgs = api::tests::create_simple_messages(6);
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_seed() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(20);
+        let sample = |seed: &str| {
+            let mut output = Cursor::new(Vec::new());
+            let arg_vec =
+                vec!["sample", path_str, "-f=csv", "-c=field_int32", "-s=5", seed];
+            let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+            assert_eq!(true, run(&args, &mut output).is_ok());
+
+            String::from_utf8(output.into_inner()).unwrap()
+        };
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        let first = sample("--seed=42");
+
+        assert_eq!(6, first.lines().count());
+        assert_eq!(first, sample("--seed=42"));
+        assert_ne!(first, sample("--seed=43"));
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_stratify_by() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(10);
+        let arg_vec = vec![
+            "sample",
+            path_str,
+            "-f=csv",
+            "-c=field_int32,field_boolean",
+            "--stratify-by=FIELD_BOOLEAN",
+            "--per-group=2",
+            "--seed=42",
+        ];
+        let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let lines = actual.lines().skip(1).collect::<Vec<_>>();
+
+        assert_eq!(4, lines.len());
+        assert_eq!(2, lines.iter().filter(|l| l.ends_with(",true")).count());
+        assert_eq!(2, lines.iter().filter(|l| l.ends_with(",false")).count());
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_stratify_by_not_selected() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(10);
+        let arg_vec = vec![
+            "sample",
+            path_str,
+            "-f=csv",
+            "-c=field_int32",
+            "--stratify-by=field_boolean",
+            "--per-group=2",
+            "--seed=42",
+        ];
+        let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let lines = actual.lines().collect::<Vec<_>>();
+        let values = lines[1..]
+            .iter()
+            .map(|l| l.parse::<i32>().unwrap())
+            .collect::<Vec<_>>();
+
+        // only the selected column is written, even values are the true group
+        assert_eq!("field_int32", lines[0]);
+        assert_eq!(4, values.len());
+        assert_eq!(2, values.iter().filter(|v| *v % 2 == 0).count());
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_stratify_by_unknown() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(2);
+        let arg_vec = vec!["sample", path_str, "--stratify-by=field_boolen"];
+        let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(
+            Err(api::Error::UnknownColumn(
+                String::from("field_boolen"),
+                vec![String::from("field_boolean"), String::from("field_double")]
+            )),
+            run(&args, &mut Cursor::new(Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_sample_per_group_requires_stratify_by() {
+        let arg_vec = vec!["sample", "file.parquet", "--per-group=2"];
+
+        assert_eq!(true, def().get_matches_from_safe(arg_vec).is_err());
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_row_groups() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(6);
+        let arg_vec = vec![
+            "sample",
+            path_str,
+            "-f=csv",
+            "-c=field_int32",
+            "--row-groups=2",
+            "--seed=42",
+        ];
+        let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_row_groups_parquet(
+            &parquet.path(),
+            &[&msgs[0..2], &msgs[2..4], &msgs[4..6]],
+        );
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let values = actual
+            .lines()
+            .skip(1)
+            .map(|l| l.parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+
+        // whole row groups, in the order they're stored
+        assert_eq!(4, values.len());
+        assert_eq!(values[0] + 1, values[1]);
+        assert_eq!(values[2] + 1, values[3]);
+        assert_eq!(true, values[1] < values[2]);
+        assert_eq!(1, values[0] % 2);
+        assert_eq!(1, values[2] % 2);
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_row_groups_and_sample() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(9);
+        let arg_vec = vec![
+            "sample",
+            path_str,
+            "-f=csv",
+            "-c=field_int32",
+            "--row-groups=2",
+            "-s=4",
+            "--seed=42",
+        ];
+        let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_row_groups_parquet(
+            &parquet.path(),
+            &[&msgs[0..3], &msgs[3..6], &msgs[6..9]],
+        );
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let row_groups = actual
+            .lines()
+            .skip(1)
+            .map(|l| (l.parse::<usize>().unwrap() - 1) / 3)
+            .collect::<Vec<_>>();
+
+        // the sample size limits the rows of the selected row groups
+        assert_eq!(4, row_groups.len());
+        assert_eq!(2, row_groups.iter().collect::<HashSet<_>>().len());
+    }
+
+    #[test]
+    fn test_sample_simple_messages_with_fraction() {
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let msgs = api::tests::create_simple_messages(10);
+        let sample = |fraction: &str| {
+            let mut output = Cursor::new(Vec::new());
+            let arg_vec = vec!["sample", path_str, "-f=csv", "-c=field_int32", fraction];
+            let args = def().get_matches_from_safe(arg_vec).unwrap();
+
+            assert_eq!(true, run(&args, &mut output).is_ok());
+
+            String::from_utf8(output.into_inner()).unwrap()
+        };
+
+        api::tests::write_simple_messages_parquet(&parquet.path(), &msgs);
+
+        assert_eq!(11, sample("--fraction=1").lines().count());
+        assert_eq!(1, sample("--fraction=0").lines().count());
+    }
+
+    #[test]
+    fn test_sample_fraction_conflicts_with_stratify_by() {
+        let arg_vec = vec![
+            "sample",
+            "file.parquet",
+            "--fraction=0.5",
+            "--stratify-by=field_boolean",
+        ];
+
+        assert_eq!(true, def().get_matches_from_safe(arg_vec).is_err());
+        assert_eq!(
+            true,
+            def()
+                .get_matches_from_safe(vec!["sample", "file.parquet", "--fraction=2"])
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,554 @@
+use crate::api::Result;
+use crate::command::args;
+use crate::output::{OutputFormat, OutputWriter};
+use crate::reader::ParquetFile;
+use crate::value::Value;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use either::Either;
+use stats::{MinMax, OnlineStats};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::mem;
+
+/// Number of bits of the hash used to pick a register of the distinct count sketch,
+/// 16384 registers give a standard error of about 0.8%.
+const DISTINCT_PRECISION: u32 = 14;
+
+/// Number of values kept by each level of the quantile sketch, quantiles are exact
+/// until the first level is full.
+const QUANTILE_CAPACITY: usize = 4096;
+
+/// HyperLogLog sketch estimating the number of distinct values in constant memory.
+struct Distinct {
+    registers: Vec<u8>,
+}
+
+impl Distinct {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << DISTINCT_PRECISION],
+        }
+    }
+
+    fn add(&mut self, hash: u64) {
+        let index = (hash >> (64 - DISTINCT_PRECISION)) as usize;
+        // the guard bit bounds the rank when the remaining bits are all zero
+        let rest = (hash << DISTINCT_PRECISION) | (1 << (DISTINCT_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|r| 2f64.powi(-i32::from(*r)))
+            .sum();
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        let estimate = alpha * m * m / sum;
+
+        // linear counting is more accurate for small cardinalities
+        if estimate <= 2.5 * m && zeros > 0 {
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+
+        estimate.round() as u64
+    }
+}
+
+/// Sketch of numeric values estimating quantiles in logarithmic memory.
+///
+/// Each level holds values with twice the weight of the previous one, a full level is
+/// sorted and every other value is promoted to the next level.
+struct Quantiles {
+    levels: Vec<Vec<f64>>,
+    compactions: usize,
+}
+
+impl Quantiles {
+    fn new() -> Self {
+        Self {
+            levels: vec![Vec::new()],
+            compactions: 0,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        let mut level = 0;
+
+        self.levels[0].push(value);
+
+        while self.levels[level].len() >= QUANTILE_CAPACITY {
+            let mut values = mem::take(&mut self.levels[level]);
+            // alternate the promoted half so the error doesn't accumulate in one
+            // direction
+            let offset = self.compactions % 2;
+
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+            if level + 1 == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+
+            self.levels[level + 1].extend(values.into_iter().skip(offset).step_by(2));
+            self.compactions += 1;
+            level += 1;
+        }
+    }
+
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let mut values = self
+            .levels
+            .iter()
+            .enumerate()
+            .flat_map(|(l, vec)| vec.iter().map(move |v| (*v, 1u64 << l)))
+            .collect::<Vec<_>>();
+
+        values.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        if self.compactions == 0 {
+            let sorted = values.iter().map(|v| v.0).collect::<Vec<_>>();
+
+            return quantile(&sorted, q);
+        }
+
+        let total = values.iter().map(|v| v.1).sum::<u64>();
+        let rank = q * (total - 1) as f64;
+        let mut weight = 0;
+
+        values
+            .iter()
+            .find(|v| {
+                weight += v.1;
+                weight as f64 > rank
+            })
+            .or_else(|| values.last())
+            .map(|v| v.0)
+    }
+}
+
+/// Summary of the values of a single column.
+///
+/// Distinct counts and quantiles are estimated with bounded sketches, so large columns
+/// don't have to fit in memory.
+struct Summary {
+    count: u64,
+    nulls: u64,
+    distinct: Distinct,
+    min: Option<Value>,
+    max: Option<Value>,
+    online: OnlineStats,
+    quantiles: Quantiles,
+    lengths: MinMax<usize>,
+}
+
+impl Summary {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            nulls: 0,
+            distinct: Distinct::new(),
+            min: None,
+            max: None,
+            online: OnlineStats::new(),
+            quantiles: Quantiles::new(),
+            lengths: MinMax::new(),
+        }
+    }
+
+    fn add(&mut self, value: &Value) {
+        self.count += 1;
+
+        if *value == Value::Null {
+            self.nulls += 1;
+            return;
+        }
+
+        let mut hasher = DefaultHasher::new();
+
+        value.to_string().hash(&mut hasher);
+        self.distinct.add(hasher.finish());
+
+        // lists and groups have no ordering
+        if value.compare(value).is_some() {
+            let is = |bound: &Option<Value>, ordering| {
+                bound
+                    .as_ref()
+                    .map(|b| value.compare(b) == Some(ordering))
+                    .unwrap_or(true)
+            };
+
+            if is(&self.min, Ordering::Less) {
+                self.min = Some(value.clone());
+            }
+
+            if is(&self.max, Ordering::Greater) {
+                self.max = Some(value.clone());
+            }
+        }
+
+        if let Some(number) = value.as_f64() {
+            self.online.add(number);
+            self.quantiles.add(number);
+        }
+
+        if let Value::Str(string) = value {
+            self.lengths.add(string.chars().count());
+        }
+    }
+}
+
+/// Computes a quantile of sorted values using linear interpolation.
+#[inline]
+fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * fraction)
+}
+
+fn compute<I>(num_fields: usize, iter: I) -> Result<Vec<Summary>>
+where
+    I: Iterator<Item = Result<Vec<Value>>>,
+{
+    let mut vec: Vec<_> = (0..num_fields).map(|_| Summary::new()).collect();
+
+    for row in iter {
+        for (i, val) in row?.iter().enumerate() {
+            vec[i].add(val);
+        }
+    }
+
+    Ok(vec)
+}
+
+#[inline]
+fn double_cell(value: Option<f64>) -> Either<String, Value> {
+    value
+        .map(|v| Either::Right(Value::Double(v)))
+        .unwrap_or_else(|| Either::Left(String::new()))
+}
+
+#[inline]
+fn value_cell(value: Option<&Value>) -> Either<String, Value> {
+    value
+        .map(|v| Either::Right(v.clone()))
+        .unwrap_or_else(|| Either::Left(String::new()))
+}
+
+#[inline]
+fn length_cell(value: Option<&usize>) -> Either<String, Value> {
+    value
+        .map(|v| Either::Right(Value::UInt(*v as u64)))
+        .unwrap_or_else(|| Either::Left(String::new()))
+}
+
+/// Formats the summary of a column, statistics that don't apply to the column are left
+/// empty.
+fn format_row(field: &str, summary: &Summary) -> Vec<Either<String, Value>> {
+    let is_numeric = summary.online.len() > 0;
+    let mean = Some(summary.online.mean()).filter(|_| is_numeric);
+    let stddev = Some(summary.online.stddev()).filter(|_| is_numeric);
+
+    vec![
+        Either::Left(field.to_string()),
+        Either::Right(Value::UInt(summary.count)),
+        Either::Right(Value::UInt(summary.nulls)),
+        Either::Right(Value::UInt(summary.distinct.estimate())),
+        value_cell(summary.min.as_ref()),
+        value_cell(summary.max.as_ref()),
+        double_cell(mean),
+        double_cell(stddev),
+        double_cell(summary.quantiles.quantile(0.25)),
+        double_cell(summary.quantiles.quantile(0.5)),
+        double_cell(summary.quantiles.quantile(0.75)),
+        length_cell(summary.lengths.min()),
+        length_cell(summary.lengths.max()),
+    ]
+}
+
+fn format_rows(
+    fields: Vec<String>,
+    vec: Vec<Summary>,
+) -> impl Iterator<Item = Result<Vec<Either<String, Value>>>> {
+    vec.into_iter()
+        .enumerate()
+        .map(move |t| Ok(format_row(&fields[t.0], &t.1)))
+}
+
+pub fn def() -> App<'static, 'static> {
+    SubCommand::with_name("stats")
+        .about("Show summary statistics for each column")
+        .arg(
+            Arg::with_name("columns")
+                .help("Select columns from parquet")
+                .takes_value(true)
+                .long("columns")
+                .multiple(true)
+                .short("c"),
+        )
+        .arg(
+            Arg::with_name("search")
+                .validator(args::validate_filter)
+                .help("Search columns")
+                .takes_value(true)
+                .long("search")
+                .multiple(true)
+                .short("s"),
+        )
+        .arg(
+            Arg::with_name("where")
+                .validator(args::validate_expression)
+                .help("Filter rows using an expression")
+                .takes_value(true)
+                .long("where")
+                .short("w"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .help("Output format")
+                .possible_values(&OutputFormat::values())
+                .default_value("table")
+                .long("format")
+                .short("f"),
+        )
+        .args(&args::csv_args())
+        .args(&args::table_args())
+        .args(&args::text_args())
+        .arg(
+            Arg::with_name("path")
+                .validator(args::validate_path)
+                .help("Path to parquet")
+                .required(true)
+                .index(1),
+        )
+}
+
+pub fn run<W: Write>(matches: &ArgMatches, out: &mut W) -> Result<()> {
+    let format = args::output_format_value(matches, "format")?;
+    let columns = args::string_values(matches, "columns")?;
+    let search = args::filter_values(matches, "search")?;
+    let expression = args::expression_value(matches, "where")?;
+    let text_options = args::text_options_value(matches)?;
+    let path = args::path_value(matches, "path")?;
+    let parquet = ParquetFile::from(path)
+        .with_fields(columns)
+        .with_filters(search)
+        .with_expression(expression);
+
+    let fields = parquet.field_names()?;
+    let vec = compute(fields.len(), parquet.iter())?;
+    let headers = vec![
+        String::from("FIELD"),
+        String::from("COUNT"),
+        String::from("NULLS"),
+        String::from("DISTINCT"),
+        String::from("MIN"),
+        String::from("MAX"),
+        String::from("MEAN"),
+        String::from("STDDEV"),
+        String::from("Q1"),
+        String::from("MEDIAN"),
+        String::from("Q3"),
+        String::from("MIN_LENGTH"),
+        String::from("MAX_LENGTH"),
+    ];
+
+    let iter = format_rows(fields, vec);
+    let csv_options = args::csv_options_value(matches)?;
+    let table_options = args::table_options_value(matches)?;
+    let mut writer = OutputWriter::new(headers, iter)
+        .format(format)
+        .csv_options(csv_options)
+        .table_options(table_options)
+        .text_options(text_options);
+
+    writer.write(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+    use std::io::Cursor;
+    use std::str;
+
+    #[test]
+    fn test_stats_quantile() {
+        let values = vec![1.0, 2.0, 3.0, 4.0];
+
+        assert_eq!(None, quantile(&[], 0.5));
+        assert_eq!(Some(5.0), quantile(&[5.0], 0.25));
+        assert_eq!(Some(1.0), quantile(&values, 0.0));
+        assert_eq!(Some(1.75), quantile(&values, 0.25));
+        assert_eq!(Some(2.5), quantile(&values, 0.5));
+        assert_eq!(Some(3.25), quantile(&values, 0.75));
+        assert_eq!(Some(4.0), quantile(&values, 1.0));
+    }
+
+    #[test]
+    fn test_stats_distinct() {
+        let mut distinct = Distinct::new();
+        let hash = |i: u64| {
+            let mut hasher = DefaultHasher::new();
+
+            i.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(0, distinct.estimate());
+
+        for i in 0..100_000 {
+            distinct.add(hash(i % 50_000));
+        }
+
+        let estimate = distinct.estimate() as f64;
+
+        assert_eq!(1 << DISTINCT_PRECISION, distinct.registers.len());
+        assert_eq!(true, (estimate - 50_000.0).abs() < 50_000.0 * 0.03);
+    }
+
+    #[test]
+    fn test_stats_quantiles() {
+        let mut quantiles = Quantiles::new();
+
+        assert_eq!(None, quantiles.quantile(0.5));
+
+        for i in (0..100_000).rev() {
+            quantiles.add(f64::from(i));
+        }
+
+        let size = quantiles.levels.iter().map(Vec::len).sum::<usize>();
+        let error = |q: f64| (quantiles.quantile(q).unwrap() - q * 100_000.0).abs();
+
+        assert_eq!(true, size < QUANTILE_CAPACITY * 4);
+        assert_eq!(true, error(0.25) < 100_000.0 * 0.01);
+        assert_eq!(true, error(0.5) < 100_000.0 * 0.01);
+        assert_eq!(true, error(0.75) < 100_000.0 * 0.01);
+    }
+
+    #[test]
+    fn test_stats_summary() {
+        let mut summary = Summary::new();
+
+        summary.add(&Value::Int(3));
+        summary.add(&Value::Null);
+        summary.add(&Value::Double(1.5));
+        summary.add(&Value::Int(3));
+
+        assert_eq!(4, summary.count);
+        assert_eq!(1, summary.nulls);
+        assert_eq!(2, summary.distinct.estimate());
+        assert_eq!(Some(Value::Double(1.5)), summary.min);
+        assert_eq!(Some(Value::Int(3)), summary.max);
+        assert_eq!(Some(3.0), summary.quantiles.quantile(0.5));
+        assert_eq!(None, summary.lengths.min());
+    }
+
+    #[test]
+    fn test_stats_simple_messages() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(4);
+        let arg_vec = vec![
+            "stats",
+            path_str,
+            "-f=csv",
+            "-c=field_int32,field_string,field_boolean",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!(
+            actual.lines().collect::<Vec<_>>(),
+            vec![
+                "FIELD,COUNT,NULLS,DISTINCT,MIN,MAX,MEAN,STDDEV,Q1,MEDIAN,Q3,MIN_LENGTH,MAX_LENGTH",
+                "field_int32,4,0,4,1,4,2.5,1.118033988749895,1.75,2.5,3.25,,",
+                "field_string,4,0,4,even 22222,odd 33333,,,,,,9,10",
+                "field_boolean,4,0,2,false,true,,,,,,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_simple_messages_with_filters() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(5);
+        let arg_vec = vec![
+            "stats",
+            path_str,
+            "-f=csv",
+            "-s=field_boolean:false",
+            "-c=field_int64",
+        ];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let lines = actual.lines().collect::<Vec<_>>();
+
+        assert_eq!(2, lines.len());
+        assert!(lines[1].starts_with("field_int64,3,0,3,11,55,33.0,"));
+        assert!(lines[1].ends_with(",22.0,33.0,44.0,,"));
+    }
+
+    #[test]
+    fn test_stats_simple_messages_json_format() {
+        let mut output = Cursor::new(Vec::new());
+        let parquet = api::tests::temp_file("msg", ".parquet");
+        let path_str = parquet.path().to_str().unwrap();
+        let path = parquet.path();
+
+        let subcomand = def();
+        let msgs = api::tests::create_simple_messages(4);
+        let arg_vec = vec!["stats", path_str, "-f=json", "-c=field_int32,field_boolean"];
+        let args = subcomand.get_matches_from_safe(arg_vec).unwrap();
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        assert_eq!(true, run(&args, &mut output).is_ok());
+
+        let vec = output.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let lines = actual.lines().collect::<Vec<_>>();
+
+        assert_eq!(2, lines.len());
+        assert!(lines[0].starts_with(
+            "{\"FIELD\":\"field_int32\",\"COUNT\":4,\"NULLS\":0,\"DISTINCT\":4,"
+        ));
+        assert!(lines[0].contains("\"MIN\":1,\"MAX\":4,\"MEAN\":2.5,"));
+        assert!(lines[1].contains("\"MIN\":false,\"MAX\":true,\"MEAN\":\"\","));
+    }
+}
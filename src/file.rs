@@ -0,0 +1,162 @@
+use crate::api::{Error, Result};
+use flate2::write::GzEncoder;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Creates a new file, existing files are only replaced when `force` is set.
+pub fn create(path: &Path, force: bool) -> Result<File> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .create_new(!force)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => Error::FileExists(path.to_path_buf()),
+            _ => Error::from(e),
+        })
+}
+
+/// Writer of an output file, compressed or not.
+enum Encoder {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<BufWriter<File>>),
+}
+
+/// Writes output into a file, compressed according to the file extension.
+pub struct OutputFile {
+    path: PathBuf,
+    encoder: Encoder,
+}
+
+impl OutputFile {
+    /// Creates the output file, using gzip for `.gz` and zstd for `.zst` extensions.
+    pub fn create(path: &Path, force: bool) -> Result<Self> {
+        let writer = BufWriter::new(create(path, force)?);
+        let extension = path.extension().and_then(|e| e.to_str());
+        let encoder = match extension {
+            Some("gz") => Encoder::Gzip(GzEncoder::new(writer, Default::default())),
+            Some("zst") => Encoder::Zstd(zstd::Encoder::new(writer, 0)?),
+            _ => Encoder::Plain(writer),
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            encoder,
+        })
+    }
+
+    /// Writes the compression trailer and flushes the file.
+    pub fn finish(self) -> io::Result<()> {
+        let mut writer = match self.encoder {
+            Encoder::Plain(writer) => writer,
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            Encoder::Zstd(encoder) => encoder.finish()?,
+        };
+
+        writer.flush()
+    }
+
+    /// Removes the file, used when the command fails so no partial output is left.
+    pub fn discard(self) -> io::Result<()> {
+        drop(self.encoder);
+
+        fs::remove_file(&self.path)
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.encoder {
+            Encoder::Plain(ref mut writer) => writer.write(buf),
+            Encoder::Gzip(ref mut encoder) => encoder.write(buf),
+            Encoder::Zstd(ref mut encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.encoder {
+            Encoder::Plain(ref mut writer) => writer.flush(),
+            Encoder::Gzip(ref mut encoder) => encoder.flush(),
+            Encoder::Zstd(ref mut encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_file_create() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("out.csv");
+
+        assert_eq!(true, create(&path, false).is_ok());
+        assert_eq!(
+            Error::FileExists(path.clone()),
+            create(&path, false).unwrap_err()
+        );
+        assert_eq!(true, create(&path, true).is_ok());
+    }
+
+    #[test]
+    fn test_file_output_plain() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("out.csv");
+        let mut file = OutputFile::create(&path, false).unwrap();
+
+        file.write_all(b"a,b\n1,2\n").unwrap();
+        file.finish().unwrap();
+
+        assert_eq!("a,b\n1,2\n", fs::read_to_string(&path).unwrap());
+    }
+
+    #[test]
+    fn test_file_output_gzip() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("out.csv.gz");
+        let mut file = OutputFile::create(&path, false).unwrap();
+        let mut actual = String::new();
+
+        file.write_all(b"a,b\n1,2\n").unwrap();
+        file.finish().unwrap();
+
+        GzDecoder::new(fs::File::open(&path).unwrap())
+            .read_to_string(&mut actual)
+            .unwrap();
+
+        assert_eq!("a,b\n1,2\n", actual);
+    }
+
+    #[test]
+    fn test_file_output_discard() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("out.csv.gz");
+        let mut file = OutputFile::create(&path, false).unwrap();
+
+        file.write_all(b"a,b\n").unwrap();
+        file.discard().unwrap();
+
+        assert_eq!(false, path.exists());
+    }
+
+    #[test]
+    fn test_file_output_zstd() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("out.csv.zst");
+        let mut file = OutputFile::create(&path, false).unwrap();
+
+        file.write_all(b"a,b\n1,2\n").unwrap();
+        file.finish().unwrap();
+
+        let actual = zstd::decode_all(fs::File::open(&path).unwrap()).unwrap();
+
+        assert_eq!(b"a,b\n1,2\n".to_vec(), actual);
+    }
+}
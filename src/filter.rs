@@ -0,0 +1,857 @@
+use crate::api::{Error, Result};
+use crate::path;
+use crate::pushdown::Predicate;
+use crate::value::Value;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use parquet::basic::{LogicalType, Type as PhysicalType};
+use parquet::schema::types::Type;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Comparison operator of a filter expression.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    /// Checks if the ordering of two values satisfies the operator.
+    pub fn test(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// Literal of a filter expression, before it's converted to the type of a column.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Literal {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Literal::Bool(value) => write!(f, "{}", value),
+            Literal::Int(value) => write!(f, "{}", value),
+            Literal::Float(value) => write!(f, "{}", value),
+            Literal::Str(value) => write!(f, "'{}'", value),
+        }
+    }
+}
+
+/// Filter expression as written by the user.
+///
+/// ie: `age >= 21 AND (country = 'NZ' OR country = 'AU') AND email IS NOT NULL`
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expr {
+    /// Compares a column with a literal.
+    Compare(String, Op, Literal),
+
+    /// Matches the text of a column with a regex.
+    Match(String, String),
+
+    /// Column value is null.
+    IsNull(String),
+
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parses a filter expression.
+    pub fn parse(text: &str) -> Result<Expr> {
+        let mut parser = Parser::new(text)?;
+        let expr = parser.parse_or()?;
+
+        match parser.peek() {
+            Some(_) => Err(parser.unexpected("end of expression")),
+            None => Ok(expr),
+        }
+    }
+
+    /// Names of the columns used by the expression, in order of appearance.
+    pub fn columns(&self) -> Vec<String> {
+        let mut result = Vec::new();
+
+        self.collect_columns(&mut result);
+
+        result
+    }
+
+    fn collect_columns(&self, result: &mut Vec<String>) {
+        let name = match self {
+            Expr::Compare(name, _, _) | Expr::Match(name, _) | Expr::IsNull(name) => name,
+            Expr::Not(expr) => return expr.collect_columns(result),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.collect_columns(result);
+                return right.collect_columns(result);
+            }
+        };
+
+        if !result.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            result.push(name.clone());
+        }
+    }
+}
+
+/// Filter expression bound to the columns of a row.
+///
+/// Comparisons with null values are unknown, like in SQL neither the comparison nor its
+/// negation match. Comparisons with lists match when any element matches.
+#[derive(Clone, Debug)]
+pub enum Filter {
+    Compare((usize, String), Op, Value),
+    Match((usize, String), Regex),
+    IsNull((usize, String)),
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Filter {
+    /// Type checks an expression against the schema.
+    ///
+    /// `columns` are the row index and name of each column used by the expression.
+    pub fn bind(
+        expr: &Expr,
+        schema: &Type,
+        columns: &[(usize, String)],
+    ) -> Result<Filter> {
+        let bind = |e: &Expr| Filter::bind(e, schema, columns).map(Box::new);
+
+        match expr {
+            Expr::Compare(name, op, literal) => {
+                let column = find_column(columns, name)?;
+                let field = find_field(schema, &column.1)?;
+                let value = typed_value(field, literal).ok_or_else(|| {
+                    Error::Filter(format!(
+                        "Invalid value {} for column '{}' of type {}",
+                        literal,
+                        column.1,
+                        type_name(field)
+                    ))
+                })?;
+
+                Ok(Filter::Compare(column, *op, value))
+            }
+            Expr::Match(name, regex) => Ok(Filter::Match(
+                find_column(columns, name)?,
+                Regex::new(regex)?,
+            )),
+            Expr::IsNull(name) => Ok(Filter::IsNull(find_column(columns, name)?)),
+            Expr::Not(expr) => Ok(Filter::Not(bind(expr)?)),
+            Expr::And(left, right) => Ok(Filter::And(bind(left)?, bind(right)?)),
+            Expr::Or(left, right) => Ok(Filter::Or(bind(left)?, bind(right)?)),
+        }
+    }
+
+    /// Evaluates the filter against the column values of a row.
+    pub fn is_match(&self, row: &[Value]) -> bool {
+        self.eval(row) == Some(true)
+    }
+
+    /// Evaluates the filter with three-valued logic, `None` when the result is unknown.
+    fn eval(&self, row: &[Value]) -> Option<bool> {
+        match self {
+            Filter::Compare(column, op, value) => {
+                any(&row[column.0], &|v| v.compare(value).map(|o| op.test(o)))
+            }
+            Filter::Match(column, regex) => any(&row[column.0], &|v| match v {
+                Value::Str(text) => Some(regex.is_match(text)),
+                value => Some(regex.is_match(&value.to_string())),
+            }),
+            Filter::IsNull(column) => Some(row[column.0] == Value::Null),
+            Filter::Not(filter) => filter.eval(row).map(|b| !b),
+            Filter::And(left, right) => match (left.eval(row), right.eval(row)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Filter::Or(left, right) => or(left.eval(row), right.eval(row)),
+        }
+    }
+
+    /// Converts the filter into a row group predicate.
+    ///
+    /// Returns `None` when the filter can't be checked using statistics.
+    pub fn predicate(&self) -> Option<Predicate> {
+        match self {
+            Filter::Compare(column, op, value) => {
+                Some(Predicate::Compare(column.1.clone(), *op, value.clone()))
+            }
+            Filter::And(left, right) => match (left.predicate(), right.predicate()) {
+                (Some(left), Some(right)) => Some(Predicate::And(vec![left, right])),
+                (left, right) => left.or(right),
+            },
+            Filter::Or(left, right) => {
+                Some(Predicate::Or(vec![left.predicate()?, right.predicate()?]))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks the predicate against a value, or against the elements of a list.
+///
+/// The result is unknown for null values.
+#[inline]
+fn any(value: &Value, predicate: &dyn Fn(&Value) -> Option<bool>) -> Option<bool> {
+    match value {
+        Value::Null => None,
+        Value::List(vec) => {
+            let mut result = Some(false);
+
+            for v in vec {
+                result = or(result, any(v, predicate));
+            }
+
+            result
+        }
+        _ => predicate(value),
+    }
+}
+
+/// Three-valued OR, true when either side is true even if the other is unknown.
+#[inline]
+fn or(left: Option<bool>, right: Option<bool>) -> Option<bool> {
+    match (left, right) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+#[inline]
+fn find_column(columns: &[(usize, String)], name: &str) -> Result<(usize, String)> {
+    columns
+        .iter()
+        .find(|c| c.1.eq_ignore_ascii_case(name))
+        .cloned()
+        .ok_or_else(|| Error::UnknownColumn(name.to_string(), vec![]))
+}
+
+#[inline]
+fn find_field<'a>(schema: &'a Type, name: &str) -> Result<&'a Type> {
+    path::resolve(schema, name)
+        .map(|c| c.field)
+        .ok_or_else(|| Error::UnknownColumn(name.to_string(), vec![]))
+}
+
+#[inline]
+fn type_name(field: &Type) -> String {
+    if !field.is_primitive() {
+        return String::from("GROUP");
+    }
+
+    match field.get_basic_info().logical_type() {
+        LogicalType::NONE => field.get_physical_type().to_string(),
+        logical_type => logical_type.to_string(),
+    }
+}
+
+#[inline]
+fn parse_date(text: &str) -> Option<u32> {
+    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+    let days = date.signed_duration_since(NaiveDate::from_ymd(1970, 1, 1));
+
+    u32::try_from(days.num_days()).ok()
+}
+
+/// Parses a timestamp in milliseconds, using the local timezone unless one is given.
+#[inline]
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let millis = match DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S %:z") {
+        Ok(datetime) => datetime.timestamp_millis(),
+        Err(_) => {
+            let datetime = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S"))
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                        .map(|d| d.and_hms(0, 0, 0))
+                })
+                .ok()?;
+
+            Local
+                .from_local_datetime(&datetime)
+                .single()?
+                .timestamp_millis()
+        }
+    };
+
+    u64::try_from(millis).ok()
+}
+
+/// Converts a literal into a value comparable with the values of a column.
+fn typed_value(field: &Type, literal: &Literal) -> Option<Value> {
+    if !field.is_primitive() {
+        return None;
+    }
+
+    match (field.get_basic_info().logical_type(), literal) {
+        (LogicalType::DATE, Literal::Str(text)) => parse_date(text).map(Value::Date),
+        (LogicalType::TIMESTAMP_MILLIS, Literal::Str(text)) => {
+            parse_timestamp(text).map(Value::TimestampMillis)
+        }
+        (LogicalType::TIMESTAMP_MICROS, Literal::Str(text)) => {
+            parse_timestamp(text).map(|t| Value::TimestampMicros(t * 1000))
+        }
+        (LogicalType::UTF8, Literal::Str(text))
+        | (LogicalType::ENUM, Literal::Str(text))
+        | (LogicalType::JSON, Literal::Str(text)) => Some(Value::Str(text.clone())),
+        (LogicalType::DATE, _)
+        | (LogicalType::TIMESTAMP_MILLIS, _)
+        | (LogicalType::TIMESTAMP_MICROS, _)
+        | (LogicalType::UTF8, _)
+        | (LogicalType::ENUM, _)
+        | (LogicalType::JSON, _) => None,
+        (_, literal) => match (field.get_physical_type(), literal) {
+            (PhysicalType::BOOLEAN, Literal::Bool(value)) => Some(Value::Bool(*value)),
+            (PhysicalType::INT96, Literal::Str(text)) => {
+                parse_timestamp(text).map(Value::TimestampMillis)
+            }
+            (PhysicalType::BYTE_ARRAY, Literal::Str(text))
+            | (PhysicalType::FIXED_LEN_BYTE_ARRAY, Literal::Str(text)) => {
+                Some(Value::Bytes(text.as_bytes().to_vec()))
+            }
+            (PhysicalType::BOOLEAN, _)
+            | (PhysicalType::INT96, _)
+            | (PhysicalType::BYTE_ARRAY, _)
+            | (PhysicalType::FIXED_LEN_BYTE_ARRAY, _) => None,
+            (_, Literal::Int(value)) => Some(Value::Int(*value)),
+            (PhysicalType::FLOAT, Literal::Float(value)) => {
+                Some(Value::Float(*value as f32))
+            }
+            (_, Literal::Float(value)) => Some(Value::Double(*value)),
+            _ => None,
+        },
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Column(String),
+    Literal(Literal),
+    Op(Op),
+    Match,
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Is,
+    Null,
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            chars: text.char_indices().peekable(),
+        }
+    }
+
+    fn next_if(&mut self, c: char) -> bool {
+        match self.chars.peek() {
+            Some((_, next)) if *next == c => {
+                self.chars.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Skips the characters matching the predicate, returning the end position.
+    fn skip_while<P: FnMut(char) -> bool>(&mut self, mut predicate: P) -> usize {
+        while let Some(&(i, c)) = self.chars.peek() {
+            if !predicate(c) {
+                return i;
+            }
+
+            self.chars.next();
+        }
+
+        self.text.len()
+    }
+
+    /// Reads a quoted text, a repeated quote escapes the quote itself.
+    fn quoted(&mut self, start: usize, quote: char) -> Result<String> {
+        let mut result = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, c)) if c == quote && !self.next_if(quote) => return Ok(result),
+                Some((_, c)) => result.push(c),
+                None => {
+                    return Err(Error::Filter(format!(
+                        "Unterminated quote at position {}",
+                        start
+                    )))
+                }
+            }
+        }
+    }
+
+    fn number(&mut self, start: usize) -> Result<Token> {
+        let mut previous = ' ';
+        let end = self.skip_while(|c| {
+            let exponent = previous == 'e' || previous == 'E';
+            let result = c.is_ascii_alphanumeric()
+                || c == '.'
+                || (exponent && (c == '-' || c == '+'));
+
+            previous = c;
+            result
+        });
+        let text = &self.text[start..end];
+        let invalid = || Error::Filter(format!("Invalid number '{}'", text));
+
+        if text.contains(&['.', 'e', 'E'][..]) {
+            let value = text.parse().map_err(|_| invalid())?;
+
+            return Ok(Token::Literal(Literal::Float(value)));
+        }
+
+        let value = text.parse().map_err(|_| invalid())?;
+
+        Ok(Token::Literal(Literal::Int(value)))
+    }
+
+    fn word(&mut self, start: usize) -> Token {
+        // dots separate the members of nested columns, ie: `address.city`
+        let end = self.skip_while(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        let text = &self.text[start..end];
+
+        match text.to_uppercase().as_ref() {
+            "AND" => Token::And,
+            "OR" => Token::Or,
+            "NOT" => Token::Not,
+            "IS" => Token::Is,
+            "NULL" => Token::Null,
+            "TRUE" => Token::Literal(Literal::Bool(true)),
+            "FALSE" => Token::Literal(Literal::Bool(false)),
+            _ => Token::Column(text.to_string()),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(usize, Token)>> {
+        while let Some(&(start, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+                continue;
+            }
+
+            if c.is_alphabetic() || c == '_' {
+                return Ok(Some((start, self.word(start))));
+            }
+
+            self.chars.next();
+
+            let token = match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                '~' => Token::Match,
+                '=' => {
+                    self.next_if('=');
+                    Token::Op(Op::Eq)
+                }
+                '!' if self.next_if('=') => Token::Op(Op::Ne),
+                '<' if self.next_if('=') => Token::Op(Op::Le),
+                '<' if self.next_if('>') => Token::Op(Op::Ne),
+                '<' => Token::Op(Op::Lt),
+                '>' if self.next_if('=') => Token::Op(Op::Ge),
+                '>' => Token::Op(Op::Gt),
+                '\'' => Token::Literal(Literal::Str(self.quoted(start, c)?)),
+                '"' | '`' => Token::Column(self.quoted(start, c)?),
+                '-' | '0'..='9' => self.number(start)?,
+                _ => {
+                    return Err(Error::Filter(format!(
+                        "Unexpected character '{}' at position {}",
+                        c, start
+                    )))
+                }
+            };
+
+            return Ok(Some((start, token)));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Recursive descent parser, from the lowest to the highest precedence:
+///
+/// ```text
+/// or         := and ( OR and )*
+/// and        := not ( AND not )*
+/// not        := NOT not | primary
+/// primary    := '(' or ')' | column ( op literal | '~' string | IS [NOT] NULL )
+/// ```
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    length: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Self> {
+        let mut lexer = Lexer::new(text);
+        let mut tokens = Vec::new();
+
+        while let Some(token) = lexer.next_token()? {
+            tokens.push(token);
+        }
+
+        Ok(Self {
+            tokens,
+            position: 0,
+            length: text.len(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|t| &t.1)
+    }
+
+    fn next_if(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        let position = self
+            .tokens
+            .get(self.position)
+            .map(|t| t.0)
+            .unwrap_or(self.length);
+
+        Error::Filter(format!("Expected {} at position {}", expected, position))
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut expr = self.parse_and()?;
+
+        while self.next_if(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut expr = self.parse_not()?;
+
+        while self.next_if(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if self.next_if(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        if self.next_if(&Token::LParen) {
+            let expr = self.parse_or()?;
+
+            if !self.next_if(&Token::RParen) {
+                return Err(self.unexpected("')'"));
+            }
+
+            return Ok(expr);
+        }
+
+        let column = match self.peek() {
+            Some(Token::Column(name)) => name.clone(),
+            _ => return Err(self.unexpected("column")),
+        };
+
+        self.position += 1;
+
+        match self.peek() {
+            Some(Token::Op(op)) => {
+                let op = *op;
+
+                self.position += 1;
+
+                let literal = match self.peek() {
+                    Some(Token::Literal(literal)) => literal.clone(),
+                    _ => return Err(self.unexpected("literal")),
+                };
+
+                self.position += 1;
+
+                Ok(Expr::Compare(column, op, literal))
+            }
+            Some(Token::Match) => {
+                self.position += 1;
+
+                let regex = match self.peek() {
+                    Some(Token::Literal(Literal::Str(regex))) => regex.clone(),
+                    _ => return Err(self.unexpected("regex string")),
+                };
+
+                // fail early on invalid regexes
+                Regex::new(&regex)?;
+
+                self.position += 1;
+
+                Ok(Expr::Match(column, regex))
+            }
+            Some(Token::Is) => {
+                self.position += 1;
+
+                let not = self.next_if(&Token::Not);
+
+                if !self.next_if(&Token::Null) {
+                    return Err(self.unexpected("NULL"));
+                }
+
+                if not {
+                    return Ok(Expr::Not(Box::new(Expr::IsNull(column))));
+                }
+
+                Ok(Expr::IsNull(column))
+            }
+            _ => Err(self.unexpected("operator")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(name: &str, op: Op, literal: Literal) -> Box<Expr> {
+        Box::new(Expr::Compare(String::from(name), op, literal))
+    }
+
+    #[test]
+    fn test_filter_parse() {
+        let expected = Expr::And(
+            Box::new(Expr::And(
+                compare("age", Op::Ge, Literal::Int(21)),
+                Box::new(Expr::Or(
+                    compare("country", Op::Eq, Literal::Str(String::from("NZ"))),
+                    compare("country", Op::Eq, Literal::Str(String::from("AU"))),
+                )),
+            )),
+            Box::new(Expr::Not(Box::new(Expr::IsNull(String::from("email"))))),
+        );
+
+        assert_eq!(
+            Ok(expected),
+            Expr::parse(
+                "age >= 21 AND (country = 'NZ' OR country = 'AU') AND email IS NOT NULL"
+            )
+        );
+    }
+
+    #[test]
+    fn test_filter_parse_precedence() {
+        let expected = Expr::Or(
+            compare("a", Op::Eq, Literal::Int(1)),
+            Box::new(Expr::And(
+                Box::new(Expr::Not(compare("b", Op::Lt, Literal::Float(-2.5)))),
+                compare("c", Op::Ne, Literal::Bool(true)),
+            )),
+        );
+
+        assert_eq!(
+            Ok(expected),
+            Expr::parse("a == 1 or not b < -2.5 and c <> TRUE")
+        );
+    }
+
+    #[test]
+    fn test_filter_parse_tokens() {
+        assert_eq!(
+            Ok(Expr::Match(String::from("url"), String::from("^http://"))),
+            Expr::parse("url ~ '^http://'")
+        );
+        assert_eq!(
+            Ok(*compare(
+                "first name",
+                Op::Eq,
+                Literal::Str(String::from("it's"))
+            )),
+            Expr::parse("\"first name\" = 'it''s'")
+        );
+        assert_eq!(
+            Ok(*compare("n", Op::Le, Literal::Float(1e3))),
+            Expr::parse("`n`<=1e3")
+        );
+        assert_eq!(
+            Ok(Expr::IsNull(String::from("n"))),
+            Expr::parse("(((n is null)))")
+        );
+    }
+
+    #[test]
+    fn test_filter_parse_err() {
+        let err = |s: &str| Err(Error::Filter(s.to_string()));
+
+        assert_eq!(err("Expected column at position 0"), Expr::parse(""));
+        assert_eq!(err("Expected operator at position 2"), Expr::parse("a 1"));
+        assert_eq!(err("Expected literal at position 4"), Expr::parse("a = b"));
+        assert_eq!(err("Expected NULL at position 5"), Expr::parse("a IS 1"));
+        assert_eq!(err("Expected ')' at position 6"), Expr::parse("(a = 1"));
+        assert_eq!(
+            err("Expected end of expression at position 6"),
+            Expr::parse("a = 1 b = 2")
+        );
+        assert_eq!(
+            err("Unterminated quote at position 4"),
+            Expr::parse("a = 'b")
+        );
+        assert_eq!(err("Invalid number '1.2.3'"), Expr::parse("a = 1.2.3"));
+        assert_eq!(
+            err("Unexpected character '#' at position 2"),
+            Expr::parse("a # 1")
+        );
+        assert_eq!(true, Expr::parse("a ~ '('").is_err());
+    }
+
+    #[test]
+    fn test_filter_columns() {
+        let expr = Expr::parse("a = 1 AND (B = 2 OR c IS NULL) AND NOT b ~ 'x'").unwrap();
+
+        assert_eq!(vec!["a", "B", "c"], expr.columns());
+        assert_eq!(
+            vec!["events.type"],
+            Expr::parse("events.type = 'view'").unwrap().columns()
+        );
+    }
+
+    #[test]
+    fn test_filter_is_match() {
+        let column = |i: usize| (i, format!("c{}", i));
+        let int = Filter::Compare(column(0), Op::Ge, Value::Int(2));
+        let text = Filter::Match(column(1), Regex::new("^od+$").unwrap());
+        let null = Filter::IsNull(column(1));
+        let or = Filter::Or(Box::new(int.clone()), Box::new(null.clone()));
+        let not = Filter::Not(Box::new(text.clone()));
+
+        let row1 = vec![Value::Int(1), Value::Str(String::from("odd"))];
+        let row2 = vec![Value::Int(2), Value::Null];
+
+        assert_eq!(false, int.is_match(&row1));
+        assert_eq!(true, int.is_match(&row2));
+        assert_eq!(true, text.is_match(&row1));
+        assert_eq!(false, text.is_match(&row2));
+        assert_eq!(false, null.is_match(&row1));
+        assert_eq!(true, null.is_match(&row2));
+        assert_eq!(false, or.is_match(&row1));
+        assert_eq!(true, or.is_match(&row2));
+        assert_eq!(false, not.is_match(&row1));
+        assert_eq!(false, not.is_match(&row2));
+
+        // comparisons with null values are unknown, negated or not
+        let ne = Filter::Compare(column(1), Op::Ne, Value::Str(String::from("x")));
+        let eq = Filter::Compare(column(1), Op::Eq, Value::Str(String::from("x")));
+        let not_eq = Filter::Not(Box::new(eq.clone()));
+        let not_null = Filter::Not(Box::new(null.clone()));
+
+        assert_eq!(true, ne.is_match(&row1));
+        assert_eq!(false, ne.is_match(&row2));
+        assert_eq!(true, not_eq.is_match(&row1));
+        assert_eq!(false, not_eq.is_match(&row2));
+        assert_eq!(true, not_null.is_match(&row1));
+        assert_eq!(false, not_null.is_match(&row2));
+
+        // unknown results only match when the other side of an OR matches
+        let or_eq = Filter::Or(Box::new(eq.clone()), Box::new(int.clone()));
+        let not_and =
+            Filter::Not(Box::new(Filter::And(Box::new(eq), Box::new(int.clone()))));
+
+        assert_eq!(true, or_eq.is_match(&row2));
+        assert_eq!(false, not_and.is_match(&row2));
+        assert_eq!(true, not_and.is_match(&row1));
+
+        // lists match when any element matches
+        let list =
+            |vec: Vec<i64>| vec![Value::List(vec.into_iter().map(Value::Int).collect())];
+
+        assert_eq!(true, int.is_match(&list(vec![1, 3])));
+        assert_eq!(false, int.is_match(&list(vec![0, 1])));
+        assert_eq!(false, int.is_match(&list(vec![])));
+    }
+
+    #[test]
+    fn test_filter_predicate() {
+        let column = |name: &str| (0, String::from(name));
+        let compare =
+            |name: &str| Box::new(Filter::Compare(column(name), Op::Gt, Value::Int(1)));
+        let null = || Box::new(Filter::IsNull(column("n")));
+        let predicate =
+            |name: &str| Predicate::Compare(name.to_string(), Op::Gt, Value::Int(1));
+
+        assert_eq!(Some(predicate("a")), compare("a").predicate());
+        assert_eq!(None, null().predicate());
+        assert_eq!(
+            Some(Predicate::And(vec![predicate("a"), predicate("b")])),
+            Filter::And(compare("a"), compare("b")).predicate()
+        );
+        assert_eq!(
+            Some(predicate("a")),
+            Filter::And(compare("a"), null()).predicate()
+        );
+        assert_eq!(
+            Some(Predicate::Or(vec![predicate("a"), predicate("b")])),
+            Filter::Or(compare("a"), compare("b")).predicate()
+        );
+        assert_eq!(None, Filter::Or(compare("a"), null()).predicate());
+        assert_eq!(None, Filter::Not(compare("a")).predicate());
+    }
+
+    #[test]
+    fn test_filter_parse_date_time() {
+        let millis = Local.ymd(2009, 3, 31).and_hms(10, 30, 0).timestamp_millis();
+
+        assert_eq!(Some(0), parse_date("1970-01-01"));
+        assert_eq!(Some(14334), parse_date("2009-03-31"));
+        assert_eq!(None, parse_date("1969-12-31"));
+        assert_eq!(None, parse_date("2009-03-31 10:30:00"));
+
+        assert_eq!(Some(millis as u64), parse_timestamp("2009-03-31 10:30:00"));
+        assert_eq!(Some(millis as u64), parse_timestamp("2009-03-31T10:30:00"));
+        assert_eq!(
+            Some(1_238_495_400_000),
+            parse_timestamp("2009-03-31 10:30:00 +00:00")
+        );
+        assert_eq!(None, parse_timestamp("NOT A DATE"));
+    }
+}
@@ -1,36 +1,121 @@
-use clap::{App, AppSettings, ArgMatches};
+use clap::{App, AppSettings, Arg, ArgMatches};
+use file::OutputFile;
+use pager::Pager;
+use std::io::Write;
+use std::path::Path;
 use std::process;
 
 mod api;
 mod command;
+mod file;
+mod filter;
 mod output;
+mod pager;
+mod path;
+mod pushdown;
 mod reader;
+mod sink;
+mod value;
 
-fn run(matches: ArgMatches) -> api::Result<()> {
-    let out = &mut std::io::stdout();
-
+fn dispatch<W: Write>(matches: &ArgMatches, out: &mut W) -> api::Result<()> {
     match matches.subcommand() {
         ("read", Some(m)) => command::read::run(m, out),
         ("schema", Some(m)) => command::schema::run(m, out),
         ("sample", Some(m)) => command::sample::run(m, out),
         ("count", Some(m)) => command::count::run(m, out),
         ("frequency", Some(m)) => command::frequency::run(m, out),
+        ("meta", Some(m)) => command::meta::run(m, out),
+        ("stats", Some(m)) => command::stats::run(m, out),
         _ => Ok(()),
     }
 }
 
+/// Opens the file given by `--output`, unless the command writes parquet into it.
+fn output_file(matches: &ArgMatches) -> api::Result<Option<OutputFile>> {
+    let format = matches.subcommand().1.and_then(|m| m.value_of("format"));
+
+    match matches.value_of("output") {
+        Some(path) if format != Some(sink::FORMAT) => {
+            OutputFile::create(Path::new(path), matches.is_present("force")).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn run(matches: ArgMatches) -> api::Result<()> {
+    if let Some(mut file) = output_file(&matches)? {
+        // a failed command would leave a partial or corrupt compressed file
+        return match dispatch(&matches, &mut file) {
+            Ok(()) => Ok(file.finish()?),
+            Err(e) => {
+                file.discard()?;
+                Err(e)
+            }
+        };
+    }
+
+    // only page when stdout is a terminal
+    let pager = if matches.is_present("no-pager")
+        || matches.is_present("output")
+        || output::terminal_width().is_none()
+    {
+        None
+    } else {
+        Pager::from_env()
+    };
+
+    let result = match pager {
+        Some(mut pager) => {
+            let result = dispatch(&matches, &mut pager);
+
+            pager.wait()?;
+
+            result
+        }
+        None => dispatch(&matches, &mut std::io::stdout()),
+    };
+
+    // output closed early, by quitting the pager or piping into `head`
+    match result {
+        Err(api::Error::BrokenPipe) => Ok(()),
+        _ => result,
+    }
+}
+
 fn main() {
     let app = App::new("xpq")
         .version(env!("CARGO_PKG_VERSION"))
         .setting(AppSettings::ArgRequiredElseHelp)
         .author("Fabio B. Silva <fabio.bat.silva@gmail.com>")
         .about("Simple Parquet command line toolkit.")
+        .arg(
+            Arg::with_name("no-pager")
+                .help("Write to stdout instead of a pager")
+                .long("no-pager")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .help("Write to a file, compressed when it ends with .gz or .zst")
+                .takes_value(true)
+                .long("output")
+                .short("o")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("force")
+                .help("Overwrite the output file when it already exists")
+                .long("force")
+                .global(true),
+        )
         .subcommands(vec![
             command::read::def(),
             command::count::def(),
             command::schema::def(),
             command::sample::def(),
             command::frequency::def(),
+            command::meta::def(),
+            command::stats::def(),
         ]);
 
     if let Err(e) = run(app.get_matches()) {
@@ -1,11 +1,18 @@
 use crate::api::{Error, Result};
+use crate::value::{TextOptions, TimestampFormat, Value};
+use csv::QuoteStyle;
+use either::Either;
 use std::cmp;
 use std::convert::TryFrom;
 use std::io::Write;
 use std::str;
 use tabwriter::TabWriter;
+use terminal_size::{terminal_size, Width};
 use unicode_width::UnicodeWidthStr;
 
+/// Number of spaces between the columns of the tabular format.
+const PADDING: usize = 2;
+
 #[inline]
 fn format_cell(value: &str, width: usize) -> String {
     let length = UnicodeWidthStr::width(value);
@@ -33,29 +40,15 @@ fn format_cell(value: &str, width: usize) -> String {
 }
 
 #[inline]
-fn format_row(
-    index: usize,
-    batch_size: usize,
-    cells: &[String],
-    width: &mut [usize],
-) -> Vec<u8> {
+fn format_row(cells: &[String], width: &[usize], pad: bool) -> Vec<u8> {
     let mut row = cells
         .iter()
         .enumerate()
-        .map(|e| {
-            // collect max width for first x rows
-            if index < batch_size {
-                if width.len() > e.0 {
-                    let len = UnicodeWidthStr::width(e.1.as_str());
-                    let max = cmp::max(len, width[e.0]);
-
-                    width[e.0] = max;
-                }
-
-                return e.1.to_owned();
+        .map(|e| match width.get(e.0) {
+            Some(w) if pad || UnicodeWidthStr::width(e.1.as_str()) > *w => {
+                format_cell(&e.1, *w)
             }
-
-            format_cell(&e.1, width[e.0])
+            _ => e.1.to_owned(),
         })
         .collect::<Vec<_>>()
         .join("\t");
@@ -65,21 +58,98 @@ fn format_row(
     row.into_bytes()
 }
 
-fn write_tabular<W: Write>(
-    values: &mut dyn Iterator<Item = Result<Vec<String>>>,
+#[inline]
+fn update_widths(width: &mut [usize], cells: &[String]) {
+    for (i, cell) in cells.iter().enumerate().take(width.len()) {
+        width[i] = cmp::max(UnicodeWidthStr::width(cell.as_str()), width[i]);
+    }
+}
+
+/// Shrinks the widest columns first until the table fits the available width.
+///
+/// Columns are never shrunk below `minwidth`.
+fn fit_widths(width: &mut [usize], available: usize, minwidth: usize) {
+    let padding = PADDING * width.len().saturating_sub(1);
+    let mut total = width.iter().sum::<usize>() + padding;
+
+    while total > available {
+        let widest = match width.iter().max() {
+            Some(w) if *w > minwidth => *w,
+            _ => break,
+        };
+
+        let excess = total - available;
+        let count = width.iter().filter(|w| **w == widest).count();
+        let next = width.iter().filter(|w| **w < widest).max().copied();
+        let target = cmp::max(next.unwrap_or(minwidth), minwidth);
+        // shrink all the widest columns evenly, down to the next widest at most
+        let shrink = cmp::min(widest - target, cmp::max(excess / count, 1));
+
+        for w in width.iter_mut().filter(|w| **w == widest).take(excess) {
+            *w -= shrink;
+            total -= shrink;
+        }
+    }
+}
+
+#[inline]
+fn limit_widths(width: &mut [usize], config: &OutputConfig) {
+    if let Some(max) = config.table.max_width {
+        let max = cmp::max(max, config.minwidth);
+
+        for w in width.iter_mut() {
+            *w = cmp::min(*w, max);
+        }
+    }
+
+    if let Some(available) = config.table.terminal_width {
+        fit_widths(width, available, config.minwidth);
+    }
+}
+
+fn write_tabular<C: Cell, W: Write>(
+    values: &mut dyn Iterator<Item = Result<Vec<C>>>,
     config: &OutputConfig,
     headers: &[String],
     out: &mut W,
 ) -> Result<()> {
     let mut width = vec![config.minwidth; headers.len()];
-    let mut writer = TabWriter::new(out).minwidth(config.minwidth);
+    let mut writer = TabWriter::new(out)
+        .minwidth(config.minwidth)
+        .padding(PADDING);
 
-    writer.write_all(&format_row(0, config.batch_size, headers, &mut width))?;
+    // collect max width for first x rows
+    let batch = (&mut *values)
+        .take(config.batch_size)
+        .map(|vec| vec.map(|v| format_cells(&v, &config.text)))
+        .collect::<Result<Vec<_>>>()?;
+
+    update_widths(&mut width, headers);
+
+    for cells in &batch {
+        update_widths(&mut width, cells);
+    }
+
+    if config.table.truncate {
+        limit_widths(&mut width, config);
+    }
+
+    writer.write_all(&format_row(headers, &width, false))?;
+
+    for cells in &batch {
+        writer.write_all(&format_row(cells, &width, false))?;
+    }
 
     for (i, vec) in values.enumerate() {
-        writer.write_all(&format_row(i, config.batch_size, &vec?, &mut width))?;
+        let cells = format_cells(&vec?, &config.text);
 
-        if i > 0 && i % config.batch_size == 0 {
+        if !config.table.truncate {
+            update_widths(&mut width, &cells);
+        }
+
+        writer.write_all(&format_row(&cells, &width, true))?;
+
+        if i % config.batch_size == 0 {
             writer.flush()?;
         }
     }
@@ -89,8 +159,110 @@ fn write_tabular<W: Write>(
     Ok(())
 }
 
-fn write_vertical<W: Write>(
-    values: &mut dyn Iterator<Item = Result<Vec<String>>>,
+#[inline]
+fn escape_markdown(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[inline]
+fn format_markdown_row(cells: &[String]) -> Vec<u8> {
+    let mut row = cells
+        .iter()
+        .map(|c| escape_markdown(c))
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    row.insert_str(0, "| ");
+    row.push_str(" |\n");
+
+    row.into_bytes()
+}
+
+fn write_markdown<C: Cell, W: Write>(
+    values: &mut dyn Iterator<Item = Result<Vec<C>>>,
+    config: &OutputConfig,
+    headers: &[String],
+    out: &mut W,
+) -> Result<()> {
+    let separator = vec![String::from("---"); headers.len()];
+
+    out.write_all(&format_markdown_row(headers))?;
+    out.write_all(&format_markdown_row(&separator))?;
+
+    for (i, vec) in values.enumerate() {
+        out.write_all(&format_markdown_row(&format_cells(&vec?, &config.text)))?;
+
+        if i > 0 && i % config.batch_size == 0 {
+            out.flush()?;
+        }
+    }
+
+    out.flush()?;
+
+    Ok(())
+}
+
+#[inline]
+fn escape_html(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[inline]
+fn format_html_row(tag: &str, cells: &[String]) -> Vec<u8> {
+    let mut row = String::from("    <tr>");
+
+    for cell in cells {
+        row.push_str(&format!("<{}>{}</{}>", tag, escape_html(cell), tag));
+    }
+
+    row.push_str("</tr>\n");
+
+    row.into_bytes()
+}
+
+fn write_html<C: Cell, W: Write>(
+    values: &mut dyn Iterator<Item = Result<Vec<C>>>,
+    config: &OutputConfig,
+    headers: &[String],
+    out: &mut W,
+) -> Result<()> {
+    out.write_all(b"<table>\n  <thead>\n")?;
+    out.write_all(&format_html_row("th", headers))?;
+    out.write_all(b"  </thead>\n  <tbody>\n")?;
+
+    for (i, vec) in values.enumerate() {
+        out.write_all(&format_html_row("td", &format_cells(&vec?, &config.text)))?;
+
+        if i > 0 && i % config.batch_size == 0 {
+            out.flush()?;
+        }
+    }
+
+    out.write_all(b"  </tbody>\n</table>\n")?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn write_vertical<C: Cell, W: Write>(
+    values: &mut dyn Iterator<Item = Result<Vec<C>>>,
     config: &OutputConfig,
     headers: &[String],
     out: &mut W,
@@ -101,11 +273,16 @@ fn write_vertical<W: Write>(
     for (i, row) in values.enumerate() {
         writer.write_all(b"\n")?;
 
-        for (h, cell) in row?.into_iter().enumerate() {
+        for (h, cell) in format_cells(&row?, &config.text).into_iter().enumerate() {
             let header = headers[h].to_string();
             let vec = vec![format!("{}:", header), cell];
 
-            writer.write_all(&format_row(i, config.batch_size, &vec, &mut width))?;
+            // collect max width for first x rows
+            if i < config.batch_size || !config.table.truncate {
+                update_widths(&mut width, &vec);
+            }
+
+            writer.write_all(&format_row(&vec, &width, i >= config.batch_size))?;
 
             if i > 0 && i % config.batch_size == 0 {
                 writer.flush()?;
@@ -118,21 +295,39 @@ fn write_vertical<W: Write>(
     Ok(())
 }
 
-fn write_csv<W: Write>(
-    values: &mut dyn Iterator<Item = Result<Vec<String>>>,
+fn write_csv<C: Cell, W: Write>(
+    values: &mut dyn Iterator<Item = Result<Vec<C>>>,
     config: &OutputConfig,
     headers: &[String],
     out: &mut W,
 ) -> Result<()> {
+    let options = &config.csv;
+    let null = config.text.null.as_deref().unwrap_or("");
+    let default_delimiter = if config.format == OutputFormat::TSV {
+        b'\t'
+    } else {
+        b','
+    };
     let mut writer = csv::WriterBuilder::new()
-        .quote_style(csv::QuoteStyle::Never)
-        .delimiter(b',')
+        .quote_style(options.quote_style)
+        .delimiter(options.delimiter.unwrap_or(default_delimiter))
         .from_writer(out);
 
-    writer.write_record(headers)?;
+    if options.headers {
+        writer.write_record(headers)?;
+
+        if let Some(types) = &config.types {
+            writer.write_record(types)?;
+        }
+    }
 
     for (i, vec) in values.enumerate() {
-        writer.write_record(&vec?)?;
+        let fields = vec?
+            .iter()
+            .map(|c| c.to_field(&config.text).unwrap_or_else(|| null.to_string()))
+            .collect::<Vec<_>>();
+
+        writer.write_record(&fields)?;
 
         if i > 0 && i % config.batch_size == 0 {
             writer.flush()?;
@@ -144,6 +339,218 @@ fn write_csv<W: Write>(
     Ok(())
 }
 
+#[inline]
+fn escape_json(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+
+    result.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\u{08}' => result.push_str("\\b"),
+            '\u{0c}' => result.push_str("\\f"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+
+    result
+}
+
+#[inline]
+fn format_json_value(value: &Value, options: &TextOptions) -> String {
+    match value {
+        Value::Null => String::from("null"),
+        Value::Bool(v) => v.to_string(),
+        Value::Int(v) => v.to_string(),
+        Value::UInt(v) => v.to_string(),
+        Value::Float(v) if v.is_finite() => format!("{:?}", v),
+        Value::Double(v) if v.is_finite() => format!("{:?}", v),
+        Value::Float(_) | Value::Double(_) => String::from("null"),
+        Value::Decimal(_, _) => value.to_string(),
+        Value::Str(v) => escape_json(v),
+        Value::Bytes(v) if options.binary.is_none() => format!(
+            "[{}]",
+            v.iter().map(u8::to_string).collect::<Vec<_>>().join(",")
+        ),
+        Value::Date(_) | Value::TimestampMillis(_) | Value::TimestampMicros(_)
+            if options.timestamp_format == Some(TimestampFormat::EpochMillis) =>
+        {
+            value.display(options).to_string()
+        }
+        Value::Bytes(_)
+        | Value::Date(_)
+        | Value::TimestampMillis(_)
+        | Value::TimestampMicros(_) => escape_json(&value.display(options).to_string()),
+        Value::List(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(|v| format_json_value(v, options))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Value::Map(entries) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|e| {
+                    let key = match &e.0 {
+                        Value::Str(k) => escape_json(k),
+                        k => escape_json(&k.display(options).to_string()),
+                    };
+
+                    format!("{}:{}", key, format_json_value(&e.1, options))
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Value::Group(fields) => format!(
+            "{{{}}}",
+            fields
+                .iter()
+                .map(|e| format!(
+                    "{}:{}",
+                    escape_json(&e.0),
+                    format_json_value(&e.1, options)
+                ))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
+/// Annotates each header with the type of the column, ie: `id: INT64`.
+#[inline]
+fn format_headers(headers: &[String], types: &Option<Vec<String>>) -> Vec<String> {
+    match types {
+        Some(types) => headers
+            .iter()
+            .zip(types.iter())
+            .map(|e| format!("{}: {}", e.0, e.1))
+            .collect(),
+        None => headers.to_vec(),
+    }
+}
+
+#[inline]
+fn format_cells<C: Cell>(cells: &[C], options: &TextOptions) -> Vec<String> {
+    cells.iter().map(|c| c.to_text(options)).collect()
+}
+
+#[inline]
+fn format_json_row<C: Cell>(
+    headers: &[String],
+    cells: &[C],
+    options: &TextOptions,
+) -> Vec<u8> {
+    let mut row = headers
+        .iter()
+        .zip(cells.iter())
+        .map(|e| format!("{}:{}", escape_json(e.0), e.1.to_json(options)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    row.insert(0, '{');
+    row.push_str("}\n");
+
+    row.into_bytes()
+}
+
+fn write_json<C: Cell, W: Write>(
+    values: &mut dyn Iterator<Item = Result<Vec<C>>>,
+    config: &OutputConfig,
+    headers: &[String],
+    out: &mut W,
+) -> Result<()> {
+    for (i, vec) in values.enumerate() {
+        out.write_all(&format_json_row(headers, &vec?, &config.text))?;
+
+        if i > 0 && i % config.batch_size == 0 {
+            out.flush()?;
+        }
+    }
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// A single cell that can be written by `OutputWriter`.
+pub trait Cell {
+    /// Text representation used by the tabular, vertical, markdown and html formats.
+    fn to_text(&self, options: &TextOptions) -> String;
+
+    /// Unquoted text used by the csv formats, `None` for nulls.
+    fn to_field(&self, options: &TextOptions) -> Option<String>;
+
+    /// JSON representation used by the json format.
+    fn to_json(&self, options: &TextOptions) -> String;
+}
+
+impl Cell for String {
+    fn to_text(&self, _options: &TextOptions) -> String {
+        self.to_owned()
+    }
+
+    fn to_field(&self, _options: &TextOptions) -> Option<String> {
+        Some(self.to_owned())
+    }
+
+    fn to_json(&self, _options: &TextOptions) -> String {
+        escape_json(self)
+    }
+}
+
+impl Cell for Value {
+    fn to_text(&self, options: &TextOptions) -> String {
+        self.display(options).to_string()
+    }
+
+    fn to_field(&self, options: &TextOptions) -> Option<String> {
+        match self {
+            Value::Null => None,
+            Value::Str(value) => Some(value.clone()),
+            value => Some(value.display(options).to_string()),
+        }
+    }
+
+    fn to_json(&self, options: &TextOptions) -> String {
+        format_json_value(self, options)
+    }
+}
+
+/// Rows mixing plain text, like column names, with typed values.
+impl<L: Cell, R: Cell> Cell for Either<L, R> {
+    fn to_text(&self, options: &TextOptions) -> String {
+        match self {
+            Either::Left(cell) => cell.to_text(options),
+            Either::Right(cell) => cell.to_text(options),
+        }
+    }
+
+    fn to_field(&self, options: &TextOptions) -> Option<String> {
+        match self {
+            Either::Left(cell) => cell.to_field(options),
+            Either::Right(cell) => cell.to_field(options),
+        }
+    }
+
+    fn to_json(&self, options: &TextOptions) -> String {
+        match self {
+            Either::Left(cell) => cell.to_json(options),
+            Either::Right(cell) => cell.to_json(options),
+        }
+    }
+}
+
 /// Output foramt.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum OutputFormat {
@@ -155,11 +562,26 @@ pub enum OutputFormat {
 
     // CSV format
     CSV,
+
+    // Tab separated format
+    TSV,
+
+    // JSON Lines format
+    JSON,
+
+    // Markdown table format
+    Markdown,
+
+    // HTML table format
+    HTML,
 }
 
 impl OutputFormat {
     pub fn values() -> Vec<&'static str> {
-        vec!["t", "table", "tabular", "v", "vertical", "c", "csv"]
+        vec![
+            "t", "table", "tabular", "v", "vertical", "c", "csv", "tsv", "j", "json",
+            "jsonl", "md", "markdown", "html",
+        ]
     }
 }
 
@@ -169,6 +591,10 @@ impl TryFrom<String> for OutputFormat {
     fn try_from(value: String) -> Result<Self> {
         match value.to_lowercase().as_ref() {
             "csv" | "c" => Ok(OutputFormat::CSV),
+            "tsv" => Ok(OutputFormat::TSV),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "html" => Ok(OutputFormat::HTML),
+            "json" | "jsonl" | "j" => Ok(OutputFormat::JSON),
             "vertical" | "v" => Ok(OutputFormat::Vertical),
             "tabular" | "table" | "t" => Ok(OutputFormat::Tabular),
             _ => Err(Error::InvalidArgument(value)),
@@ -176,12 +602,69 @@ impl TryFrom<String> for OutputFormat {
     }
 }
 
+/// Options of the csv and tsv formats.
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    /// Field delimiter, defaults to a comma for csv and a tab for tsv.
+    pub delimiter: Option<u8>,
+
+    /// Quoting of fields.
+    pub quote_style: QuoteStyle,
+
+    /// Whether the header row is written.
+    pub headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: None,
+            quote_style: QuoteStyle::Necessary,
+            headers: true,
+        }
+    }
+}
+
+/// Options of the tabular format.
+#[derive(Clone, Debug)]
+pub struct TableOptions {
+    /// Max width of each column.
+    pub max_width: Option<usize>,
+
+    /// Whether cells wider than their column are truncated.
+    pub truncate: bool,
+
+    /// Width of the terminal the columns are fitted to, `None` when not a TTY.
+    pub terminal_width: Option<usize>,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            max_width: None,
+            truncate: true,
+            terminal_width: None,
+        }
+    }
+}
+
+/// Gets the width of the terminal attached to stdout.
+///
+/// Returns `None` when stdout is not a TTY.
+pub fn terminal_width() -> Option<usize> {
+    terminal_size().map(|(Width(w), _)| w as usize)
+}
+
 /// Output configuration.
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct OutputConfig {
     minwidth: usize,
     batch_size: usize,
     format: OutputFormat,
+    csv: CsvOptions,
+    table: TableOptions,
+    text: TextOptions,
+    types: Option<Vec<String>>,
 }
 
 impl Default for OutputConfig {
@@ -190,6 +673,10 @@ impl Default for OutputConfig {
             minwidth: 4,
             batch_size: 500,
             format: OutputFormat::Tabular,
+            csv: CsvOptions::default(),
+            table: TableOptions::default(),
+            text: TextOptions::default(),
+            types: None,
         }
     }
 }
@@ -201,9 +688,10 @@ pub struct OutputWriter<T> {
     config: OutputConfig,
 }
 
-impl<T> OutputWriter<T>
+impl<T, C> OutputWriter<T>
 where
-    T: Iterator<Item = Result<Vec<String>>>,
+    T: Iterator<Item = Result<Vec<C>>>,
+    C: Cell,
 {
     /// Create a new `OutputWriter`
     pub fn new(headers: Vec<String>, values: T) -> Self {
@@ -223,36 +711,101 @@ where
             headers: self.headers,
             config: OutputConfig {
                 format,
-                minwidth: self.config.minwidth,
-                batch_size: self.config.batch_size,
+                ..self.config
+            },
+        }
+    }
+
+    /// Set the options used by the csv and tsv formats.
+    pub fn csv_options(self, csv: CsvOptions) -> OutputWriter<T> {
+        Self {
+            values: self.values,
+            headers: self.headers,
+            config: OutputConfig { csv, ..self.config },
+        }
+    }
+
+    /// Set the options used by the tabular format.
+    pub fn table_options(self, table: TableOptions) -> OutputWriter<T> {
+        Self {
+            values: self.values,
+            headers: self.headers,
+            config: OutputConfig {
+                table,
+                ..self.config
+            },
+        }
+    }
+
+    /// Set the options used when converting values to text.
+    pub fn text_options(self, text: TextOptions) -> OutputWriter<T> {
+        Self {
+            values: self.values,
+            headers: self.headers,
+            config: OutputConfig {
+                text,
+                ..self.config
+            },
+        }
+    }
+
+    /// Set the column types shown in the headers, `None` to show names only.
+    pub fn types(self, types: Option<Vec<String>>) -> OutputWriter<T> {
+        Self {
+            values: self.values,
+            headers: self.headers,
+            config: OutputConfig {
+                types,
+                ..self.config
             },
         }
     }
 
     /// Write each row to the io Write.
+    ///
+    /// Stops reading values as soon as the io Write is closed, which is not an error.
     pub fn write<W: Write>(&mut self, out: &mut W) -> Result<()> {
-        match self.config.format {
-            OutputFormat::Tabular => {
-                write_tabular(&mut self.values, &self.config, &self.headers, out)?;
-            }
-            OutputFormat::Vertical => {
-                write_vertical(&mut self.values, &self.config, &self.headers, out)?;
-            }
-            OutputFormat::CSV => {
-                write_csv(&mut self.values, &self.config, &self.headers, out)?;
+        let values = &mut self.values;
+        let config = &self.config;
+        let headers = format_headers(&self.headers, &config.types);
+        let result = match config.format {
+            OutputFormat::Tabular => write_tabular(values, config, &headers, out),
+            OutputFormat::Vertical => write_vertical(values, config, &headers, out),
+            OutputFormat::CSV | OutputFormat::TSV => {
+                write_csv(values, config, &self.headers, out)
             }
+            OutputFormat::JSON => write_json(values, config, &self.headers, out),
+            OutputFormat::Markdown => write_markdown(values, config, &headers, out),
+            OutputFormat::HTML => write_html(values, config, &headers, out),
+        };
+
+        match result {
+            Err(Error::BrokenPipe) => Ok(()),
+            _ => result,
         }
-
-        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Cursor;
+    use crate::value::BinaryFormat;
+    use std::io::{self, Cursor, ErrorKind};
     use std::str;
 
+    /// Write closed by the reader, like a pipe into `head`.
+    struct ClosedWrite;
+
+    impl Write for ClosedWrite {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::from(ErrorKind::BrokenPipe))
+        }
+    }
+
     #[test]
     fn test_table_format_cell() {
         assert_eq!("1234...", format_cell("123456789", 7));
@@ -273,7 +826,6 @@ mod tests {
 
     #[test]
     fn test_table_format_row() {
-        let batch_size = 1;
         let mut width = vec![0; 2];
         let values = vec![
             vec!["12345".to_string(), "tÞykÂ¿".to_string()],
@@ -281,9 +833,11 @@ mod tests {
             vec!["".to_string(), "".to_string()],
         ];
 
-        let result1 = format_row(0, batch_size, &values[0], &mut width);
-        let result2 = format_row(1, batch_size, &values[1], &mut width);
-        let result3 = format_row(2, batch_size, &values[2], &mut width);
+        update_widths(&mut width, &values[0]);
+
+        let result1 = format_row(&values[0], &width, false);
+        let result2 = format_row(&values[1], &width, true);
+        let result3 = format_row(&values[2], &width, true);
 
         assert_eq!(vec![5, 6], width);
         assert_eq!(16, result1.len());
@@ -295,6 +849,109 @@ mod tests {
         assert_eq!("     \t      \n", str::from_utf8(&result3).unwrap());
     }
 
+    #[test]
+    fn test_table_format_row_without_pad() {
+        let width = vec![5, 6];
+        let values = vec!["123456789".to_string(), "".to_string()];
+
+        let result = format_row(&values, &width, false);
+
+        assert_eq!("12...\t\n", str::from_utf8(&result).unwrap());
+    }
+
+    #[test]
+    fn test_table_fit_widths() {
+        let mut width = vec![10, 30, 20];
+
+        fit_widths(&mut width, 80, 4);
+        assert_eq!(vec![10, 30, 20], width);
+
+        fit_widths(&mut width, 44, 4);
+        assert_eq!(vec![10, 15, 15], width);
+
+        fit_widths(&mut width, 28, 4);
+        assert_eq!(vec![8, 8, 8], width);
+
+        fit_widths(&mut width, 10, 4);
+        assert_eq!(vec![4, 4, 4], width);
+    }
+
+    #[test]
+    fn test_table_write_tabular_terminal_width() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![
+            Ok(vec![String::from("123"), String::from("1234567890abcdef")]),
+            Ok(vec![String::from("456"), String::from("short")]),
+        ];
+        let options = TableOptions {
+            terminal_width: Some(16),
+            ..TableOptions::default()
+        };
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).table_options(options);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = "c1    c2\n123   1234567...\n456   short\n";
+
+        assert_eq!(expected, actual);
+        assert_eq!(true, actual.lines().all(|l| l.len() <= 16));
+    }
+
+    #[test]
+    fn test_table_write_tabular_max_width() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![Ok(vec![
+            String::from("1234567890"),
+            String::from("\"abcdefghij\""),
+        ])];
+        let options = TableOptions {
+            max_width: Some(8),
+            ..TableOptions::default()
+        };
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).table_options(options);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = "c1        c2\n12345...  \"abc...\"\n";
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_table_write_tabular_no_truncate() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers = vec![String::from("c")];
+        let values = vec![
+            Ok(vec![String::from("1")]),
+            Ok(vec![String::from("1234567890")]),
+        ];
+        let options = TableOptions {
+            max_width: Some(4),
+            truncate: false,
+            terminal_width: Some(4),
+        };
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).table_options(options);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!("c\n1\n1234567890\n", actual);
+    }
+
     #[test]
     fn test_table_write_vertical() {
         let config = OutputConfig::default();
@@ -366,6 +1023,323 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_table_write_csv_quoting() {
+        let config = OutputConfig::default();
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let mut values = vec![
+            Ok(vec![Value::Str(String::from("a,b")), Value::Int(1)]),
+            Ok(vec![Value::Str(String::from("say \"hi\"")), Value::Null]),
+            Ok(vec![Value::Str(String::from("x\ny")), Value::Double(1.5)]),
+        ]
+        .into_iter();
+
+        write_csv(&mut values, &config, &headers, &mut buff).expect("Fail to write csv");
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = "c1,c2\n\"a,b\",1\n\"say \"\"hi\"\"\",\n\"x\ny\",1.5\n";
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_table_write_csv_options() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![
+            Ok(vec![Value::Str(String::from("a b")), Value::Int(1)]),
+            Ok(vec![Value::Null, Value::Int(2)]),
+        ];
+        let options = CsvOptions {
+            delimiter: Some(b';'),
+            quote_style: QuoteStyle::NonNumeric,
+            headers: false,
+        };
+        let text = TextOptions {
+            null: Some(String::from("NULL")),
+            ..TextOptions::default()
+        };
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter)
+            .format(OutputFormat::CSV)
+            .csv_options(options)
+            .text_options(text);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!("\"a b\";1\n\"NULL\";2\n", actual);
+    }
+
+    #[test]
+    fn test_table_write_tsv() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![Ok(vec![Value::Str(String::from("a,b")), Value::Int(1)])];
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).format(OutputFormat::TSV);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!("c1\tc2\na,b\t1\n", actual);
+    }
+
+    #[test]
+    fn test_table_escape_markdown() {
+        assert_eq!("abc", escape_markdown("abc"));
+        assert_eq!("a \\| b", escape_markdown("a | b"));
+        assert_eq!("a\\\\b", escape_markdown("a\\b"));
+        assert_eq!("a<br>b<br>c", escape_markdown("a\nb\r\nc"));
+    }
+
+    #[test]
+    fn test_table_write_markdown() {
+        let config = OutputConfig::default();
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let mut values = vec![
+            Ok(vec![Value::Int(1), Value::Str(String::from("a|b"))]),
+            Ok(vec![Value::Int(2), Value::Null]),
+        ]
+        .into_iter();
+
+        write_markdown(&mut values, &config, &headers, &mut buff)
+            .expect("Fail to write markdown");
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = vec![
+            "| c1 | c2 |",
+            "| --- | --- |",
+            "| 1 | \"a\\|b\" |",
+            "| 2 | null |",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_table_escape_html() {
+        assert_eq!("abc", escape_html("abc"));
+        assert_eq!(
+            "&lt;b&gt;&quot;x&quot; &amp; &#39;y&#39;&lt;/b&gt;",
+            escape_html("<b>\"x\" & 'y'</b>")
+        );
+    }
+
+    #[test]
+    fn test_table_write_html() {
+        let config = OutputConfig::default();
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("<c2>")];
+        let mut values =
+            vec![Ok(vec![Value::Int(1), Value::Str(String::from("a&b"))])].into_iter();
+
+        write_html(&mut values, &config, &headers, &mut buff)
+            .expect("Fail to write html");
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = vec![
+            "<table>",
+            "  <thead>",
+            "    <tr><th>c1</th><th>&lt;c2&gt;</th></tr>",
+            "  </thead>",
+            "  <tbody>",
+            "    <tr><td>1</td><td>&quot;a&amp;b&quot;</td></tr>",
+            "  </tbody>",
+            "</table>",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_table_escape_json() {
+        assert_eq!("\"abc\"", escape_json("abc"));
+        assert_eq!("\"a\\\"b\\\"c\"", escape_json("a\"b\"c"));
+        assert_eq!("\"a\\\\b\"", escape_json("a\\b"));
+        assert_eq!("\"a\\nb\\tc\"", escape_json("a\nb\tc"));
+        assert_eq!("\"\\u0001\"", escape_json("\u{01}"));
+        assert_eq!("\"tÞykkvibær\"", escape_json("tÞykkvibær"));
+    }
+
+    #[test]
+    fn test_table_write_json() {
+        let config = OutputConfig::default();
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let mut values = vec![
+            Ok(vec![String::from("1"), String::from("a \"b\"")]),
+            Ok(vec![String::from("2"), String::from("c\nd")]),
+        ]
+        .into_iter();
+
+        write_json(&mut values, &config, &headers, &mut buff)
+            .expect("Fail to write json");
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected = vec![
+            "{\"c1\":\"1\",\"c2\":\"a \\\"b\\\"\"}",
+            "{\"c1\":\"2\",\"c2\":\"c\\nd\"}",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_table_format_json_value() {
+        let group = Value::Group(vec![
+            (String::from("id"), Value::Int(1)),
+            (
+                String::from("tags"),
+                Value::List(vec![Value::Str("a".into())]),
+            ),
+            (
+                String::from("attrs"),
+                Value::Map(vec![(Value::Str("k".into()), Value::Null)]),
+            ),
+        ]);
+        let options = TextOptions::default();
+
+        assert_eq!("null", format_json_value(&Value::Null, &options));
+        assert_eq!("true", format_json_value(&Value::Bool(true), &options));
+        assert_eq!("-1", format_json_value(&Value::Int(-1), &options));
+        assert_eq!("3.3", format_json_value(&Value::Float(3.3), &options));
+        assert_eq!(
+            "null",
+            format_json_value(&Value::Double(f64::NAN), &options)
+        );
+        assert_eq!(
+            "-12.34",
+            format_json_value(&Value::Decimal(-1234, 2), &options)
+        );
+        assert_eq!(
+            "\"a\\\"b\"",
+            format_json_value(&Value::Str("a\"b".into()), &options)
+        );
+        assert_eq!(
+            "[1,2]",
+            format_json_value(&Value::Bytes(vec![1, 2]), &options)
+        );
+        assert_eq!(
+            "{\"id\":1,\"tags\":[\"a\"],\"attrs\":{\"k\":null}}",
+            format_json_value(&group, &options)
+        );
+    }
+
+    #[test]
+    fn test_table_format_json_value_options() {
+        let options = TextOptions {
+            null: Some(String::from("NA")),
+            timezone: Some("UTC".parse().unwrap()),
+            timestamp_format: Some(TimestampFormat::EpochMillis),
+            binary: Some(BinaryFormat::Hex),
+        };
+        let list = Value::List(vec![Value::Null, Value::Date(1)]);
+
+        assert_eq!("null", format_json_value(&Value::Null, &options));
+        assert_eq!("[null,86400000]", format_json_value(&list, &options));
+        assert_eq!(
+            "\"0102\"",
+            format_json_value(&Value::Bytes(vec![1, 2]), &options)
+        );
+        assert_eq!(
+            "1238544000000",
+            format_json_value(&Value::TimestampMillis(1_238_544_000_000), &options)
+        );
+
+        let options = TextOptions {
+            timestamp_format: Some(TimestampFormat::Iso8601),
+            ..options
+        };
+
+        assert_eq!(
+            "\"2009-04-01T00:00:00.000Z\"",
+            format_json_value(&Value::TimestampMillis(1_238_544_000_000), &options)
+        );
+    }
+
+    #[test]
+    fn test_table_output_writer_text_options() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![Ok(vec![Value::Null, Value::Bytes(vec![255])])];
+        let options = TextOptions {
+            null: Some(String::from("-")),
+            binary: Some(BinaryFormat::Base64),
+            ..TextOptions::default()
+        };
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).text_options(options);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!("c1    c2\n-     /w==\n", actual);
+    }
+
+    #[test]
+    fn test_table_output_writer_values() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![
+            Ok(vec![Value::Int(1), Value::Str(String::from("a"))]),
+            Ok(vec![Value::Null, Value::Bool(true)]),
+        ];
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).format(OutputFormat::JSON);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+        let expected =
+            vec!["{\"c1\":1,\"c2\":\"a\"}", "{\"c1\":null,\"c2\":true}", ""].join("\n");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_table_output_writer_either() {
+        let mut buff = Cursor::new(Vec::new());
+        let headers: Vec<String> = vec![String::from("c1"), String::from("c2")];
+        let values = vec![Ok(vec![
+            Either::Left(String::from("a")),
+            Either::Right(Value::UInt(2)),
+        ])];
+
+        let iter = values.into_iter();
+        let mut writer = OutputWriter::new(headers, iter).format(OutputFormat::JSON);
+
+        writer.write(&mut buff).unwrap();
+
+        let vec = buff.into_inner();
+        let actual = str::from_utf8(&vec).unwrap();
+
+        assert_eq!("{\"c1\":\"a\",\"c2\":2}\n", actual);
+    }
+
     #[test]
     fn test_table_output_format_try_from() -> Result<()> {
         assert_eq!(
@@ -398,11 +1372,36 @@ mod tests {
             OutputFormat::CSV
         );
 
+        assert_eq!(
+            OutputFormat::try_from(String::from("tsv"))?,
+            OutputFormat::TSV
+        );
+
+        assert_eq!(
+            OutputFormat::try_from(String::from("md"))?,
+            OutputFormat::Markdown
+        );
+
+        assert_eq!(
+            OutputFormat::try_from(String::from("HTML"))?,
+            OutputFormat::HTML
+        );
+
         assert_eq!(
             OutputFormat::try_from(String::from("v"))?,
             OutputFormat::Vertical
         );
 
+        assert_eq!(
+            OutputFormat::try_from(String::from("json"))?,
+            OutputFormat::JSON
+        );
+
+        assert_eq!(
+            OutputFormat::try_from(String::from("JSONL"))?,
+            OutputFormat::JSON
+        );
+
         assert_eq!(
             OutputFormat::try_from(String::from("foo")).err().unwrap(),
             Error::InvalidArgument(String::from("foo"))
@@ -415,7 +1414,10 @@ mod tests {
     fn test_table_output_format_values() {
         assert_eq!(
             OutputFormat::values(),
-            vec!["t", "table", "tabular", "v", "vertical", "c", "csv"]
+            vec![
+                "t", "table", "tabular", "v", "vertical", "c", "csv", "tsv", "j", "json",
+                "jsonl", "md", "markdown", "html"
+            ]
         );
     }
 
@@ -509,6 +1511,59 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_table_output_writer_types() {
+        let types = Some(vec![String::from("INT64"), String::from("UTF8")]);
+        let write = |format| {
+            let mut buff = Cursor::new(Vec::new());
+            let headers = vec![String::from("id"), String::from("name")];
+            let values = vec![Ok(vec![Value::Int(1), Value::Str(String::from("a"))])];
+            let mut writer = OutputWriter::new(headers, values.into_iter())
+                .format(format)
+                .types(types.clone());
+
+            writer.write(&mut buff).unwrap();
+
+            String::from_utf8(buff.into_inner()).unwrap()
+        };
+
+        assert_eq!(
+            "id: INT64  name: UTF8\n1          \"a\"\n",
+            write(OutputFormat::Tabular)
+        );
+        assert_eq!(
+            "\nid: INT64:   1\nname: UTF8:  \"a\"\n",
+            write(OutputFormat::Vertical)
+        );
+        assert_eq!(
+            "| id: INT64 | name: UTF8 |\n| --- | --- |\n| 1 | \"a\" |\n",
+            write(OutputFormat::Markdown)
+        );
+        assert_eq!("id,name\nINT64,UTF8\n1,a\n", write(OutputFormat::CSV));
+        assert_eq!("{\"id\":1,\"name\":\"a\"}\n", write(OutputFormat::JSON));
+    }
+
+    #[test]
+    fn test_table_output_writer_broken_pipe() {
+        let formats = vec![
+            OutputFormat::Tabular,
+            OutputFormat::Vertical,
+            OutputFormat::CSV,
+            OutputFormat::JSON,
+            OutputFormat::Markdown,
+            OutputFormat::HTML,
+        ];
+
+        for format in formats {
+            let headers = vec![String::from("c")];
+            let mut iter = (0..10_000).map(|n| Ok(vec![Value::Int(n)]));
+            let mut writer = OutputWriter::new(headers, &mut iter).format(format);
+
+            assert_eq!(Ok(()), writer.write(&mut ClosedWrite));
+            assert_eq!(true, iter.next().is_some(), "{:?}", format);
+        }
+    }
+
     #[test]
     fn test_table_output_writer_write_batch() {
         let mut buff = Cursor::new(Vec::new());
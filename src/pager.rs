@@ -0,0 +1,102 @@
+use std::env;
+use std::io::{self, ErrorKind, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// Pager used when `$PAGER` is not set, scrolling long lines horizontally.
+const DEFAULT_PAGER: &str = "less -S";
+
+/// Options passed to `less` when `$LESS` is not set,
+/// quit if the output fits a single screen and keep it on the terminal after exit.
+const DEFAULT_LESS: &str = "FRX";
+
+/// Pipes output into a pager process.
+pub struct Pager {
+    child: Child,
+    stdin: Option<ChildStdin>,
+}
+
+impl Pager {
+    /// Spawns the pager command.
+    ///
+    /// Returns `None` when the command is empty or can't be started.
+    pub fn spawn(command: &str) -> Option<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next()?;
+        let less = env::var("LESS").unwrap_or_else(|_| String::from(DEFAULT_LESS));
+        let mut child = Command::new(program)
+            .args(parts)
+            .env("LESS", less)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()?;
+
+        let stdin = child.stdin.take();
+
+        Some(Self { child, stdin })
+    }
+
+    /// Spawns the pager configured by `$PAGER`, defaults to `less -S`.
+    pub fn from_env() -> Option<Self> {
+        let command = env::var("PAGER").unwrap_or_else(|_| String::from(DEFAULT_PAGER));
+
+        Self::spawn(&command)
+    }
+
+    /// Closes the pager input and waits for the user to quit.
+    pub fn wait(mut self) -> io::Result<()> {
+        drop(self.stdin.take());
+
+        self.child.wait()?;
+
+        Ok(())
+    }
+}
+
+impl Write for Pager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.stdin.as_mut() {
+            Some(stdin) => stdin.write(buf),
+            None => Err(io::Error::from(ErrorKind::BrokenPipe)),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.stdin.as_mut() {
+            Some(stdin) => stdin.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_spawn_invalid() {
+        assert_eq!(true, Pager::spawn("").is_none());
+        assert_eq!(true, Pager::spawn("  ").is_none());
+        assert_eq!(true, Pager::spawn("xpq-pager-not-found -S").is_none());
+    }
+
+    #[test]
+    fn test_pager_write() {
+        let mut pager = Pager::spawn("sort -o /dev/null").unwrap();
+
+        assert_eq!(true, pager.write_all(b"line 1\nline 2\n").is_ok());
+        assert_eq!(true, pager.flush().is_ok());
+        assert_eq!(true, pager.wait().is_ok());
+    }
+
+    #[test]
+    fn test_pager_exited_early() {
+        let mut pager = Pager::spawn("true").unwrap();
+
+        pager.child.wait().unwrap();
+
+        let result = pager.write_all(b"line 1\n");
+
+        assert_eq!(ErrorKind::BrokenPipe, result.unwrap_err().kind());
+        assert_eq!(true, pager.wait().is_ok());
+    }
+}
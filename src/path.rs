@@ -0,0 +1,557 @@
+use crate::value::{Fields, Value};
+use parquet::basic::{LogicalType, Repetition};
+use parquet::schema::types::{Type, TypePtr};
+
+/// Step of a nested column path.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Segment {
+    /// Member of a group.
+    Member(String),
+
+    /// Every element of a list.
+    Elements,
+}
+
+/// Column path resolved against the schema.
+#[derive(Debug)]
+pub struct Column<'a> {
+    /// Index of the top-level field containing the column.
+    pub index: usize,
+
+    /// Dotted path using the names of the schema.
+    pub name: String,
+
+    /// Steps from the top-level field to the column.
+    pub segments: Vec<Segment>,
+
+    /// Type of the column.
+    pub field: &'a Type,
+}
+
+/// Checks if a field is a group annotated as a list.
+#[inline]
+pub fn is_list(field: &Type) -> bool {
+    field.is_group() && field.get_basic_info().logical_type() == LogicalType::LIST
+}
+
+/// Checks if a field is annotated as a map.
+#[inline]
+pub fn is_map(field: &Type) -> bool {
+    matches!(
+        field.get_basic_info().logical_type(),
+        LogicalType::MAP | LogicalType::MAP_KEY_VALUE
+    )
+}
+
+/// Gets the repeated group and the element of a list.
+///
+/// Follows the backward compatibility rules of the parquet format, where the repeated
+/// group is the element itself when it has more than one field or a legacy name.
+#[inline]
+pub fn list_element(field: &Type) -> Option<(&Type, &Type)> {
+    let repeated = field.get_fields().first()?;
+    let legacy_name = format!("{}_tuple", field.name());
+
+    if repeated.is_group()
+        && repeated.get_fields().len() == 1
+        && repeated.name() != "array"
+        && repeated.name() != legacy_name
+    {
+        return Some((repeated, &repeated.get_fields()[0]));
+    }
+
+    Some((repeated, repeated))
+}
+
+/// Checks if a field is repeated, repeated groups outside of lists are lists of groups.
+#[inline]
+fn is_repeated(field: &Type) -> bool {
+    let info = field.get_basic_info();
+
+    info.has_repetition() && info.repetition() == Repetition::REPEATED
+}
+
+#[inline]
+fn element_type_name(field: &Type) -> String {
+    let info = field.get_basic_info();
+
+    if is_list(field) {
+        let element = list_element(field).map(|e| element_type_name(e.1));
+
+        return format!("LIST<{}>", element.unwrap_or_default());
+    }
+
+    if is_map(field) {
+        let entries = field.get_fields().first().map(|e| e.get_fields());
+        let names = entries
+            .unwrap_or_default()
+            .iter()
+            .map(|f| type_name(f))
+            .collect::<Vec<_>>();
+
+        return format!("MAP<{}>", names.join(", "));
+    }
+
+    if field.is_group() {
+        return String::from("GROUP");
+    }
+
+    // precision and scale are only exposed by the primitive type itself
+    if let Type::PrimitiveType {
+        precision, scale, ..
+    } = field
+    {
+        if info.logical_type() == LogicalType::DECIMAL {
+            return format!("DECIMAL({},{})", precision, scale);
+        }
+    }
+
+    match info.logical_type() {
+        LogicalType::NONE => field.get_physical_type().to_string(),
+        logical => logical.to_string(),
+    }
+}
+
+/// Name of the type of a field, ie: `INT64`, `TIMESTAMP_MILLIS` or `LIST<UTF8>`.
+///
+/// Uses the logical type when annotated and the physical type otherwise,
+/// repeated fields are named as lists of their type.
+pub fn type_name(field: &Type) -> String {
+    let name = element_type_name(field);
+
+    if is_repeated(field) {
+        return format!("LIST<{}>", name);
+    }
+
+    name
+}
+
+#[inline]
+fn find_field(fields: &[TypePtr], name: &str) -> Option<usize> {
+    fields
+        .iter()
+        .position(|f| f.name().eq_ignore_ascii_case(name))
+}
+
+impl<'a> Column<'a> {
+    /// Name of the type of the values selected by the column.
+    ///
+    /// Columns nested in lists select a list with a value for each element.
+    pub fn type_name(&self) -> String {
+        self.segments
+            .iter()
+            .filter(|s| **s == Segment::Elements)
+            .fold(type_name(self.field), |name, _| format!("LIST<{}>", name))
+    }
+}
+
+/// Resolves a column name against the schema, ignoring case.
+///
+/// Nested columns are separated by dots, ie: `address.city`.
+/// The repeated group and element of lists can be omitted, so `events.type` and
+/// `events.list.element.type` refer to the same column.
+pub fn resolve<'a>(schema: &'a Type, name: &str) -> Option<Column<'a>> {
+    let fields = schema.get_fields();
+
+    // top-level names may contain dots
+    if let Some(index) = find_field(fields, name) {
+        return Some(Column {
+            index,
+            name: fields[index].name().to_string(),
+            segments: vec![],
+            field: &fields[index],
+        });
+    }
+
+    let parts = name.split('.').collect::<Vec<_>>();
+    let index = find_field(fields, parts[0])?;
+    let mut field: &Type = &fields[index];
+    let mut names = vec![field.name()];
+    let mut segments = vec![];
+    let mut i = 1;
+
+    while i < parts.len() {
+        if is_list(field) {
+            let (repeated, element) = list_element(field)?;
+
+            for level in &[repeated, element] {
+                if i < parts.len()
+                    && parts[i].eq_ignore_ascii_case(level.name())
+                    && names.last() != Some(&level.name())
+                {
+                    names.push(level.name());
+                    i += 1;
+                }
+            }
+
+            segments.push(Segment::Elements);
+            field = element;
+            continue;
+        }
+
+        if !field.is_group() || is_map(field) {
+            return None;
+        }
+
+        // the members of a repeated group are selected from each of its elements,
+        // unless the group is already the element of a list
+        if is_repeated(field) && segments.last() != Some(&Segment::Elements) {
+            segments.push(Segment::Elements);
+        }
+
+        let child = &field.get_fields()[find_field(field.get_fields(), parts[i])?];
+
+        names.push(child.name());
+        segments.push(Segment::Member(child.name().to_string()));
+        field = child;
+        i += 1;
+    }
+
+    Some(Column {
+        index,
+        name: names.join("."),
+        segments,
+        field,
+    })
+}
+
+/// Dotted paths of all the fields in the schema.
+pub fn names(schema: &Type) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut stack = schema
+        .get_fields()
+        .iter()
+        .rev()
+        .map(|f| (f.name().to_string(), f.as_ref()))
+        .collect::<Vec<_>>();
+
+    while let Some((name, field)) = stack.pop() {
+        if field.is_group() {
+            for child in field.get_fields().iter().rev() {
+                stack.push((format!("{}.{}", name, child.name()), child));
+            }
+        }
+
+        result.push(name);
+    }
+
+    result
+}
+
+/// Selects the value of a nested column from a top-level field.
+///
+/// Missing groups are returned as null, lists and repeated groups are returned with the
+/// selected value of each element.
+pub fn select(
+    fields: Fields,
+    i: usize,
+    field: &Type,
+    segments: &[Segment],
+) -> parquet::errors::Result<Value> {
+    match segments.first() {
+        None => fields.value(i, field),
+        Some(_) if !field.is_group() => Ok(Value::Null),
+        Some(Segment::Member(name)) => {
+            select_member(fields, i, field, name, &segments[1..])
+        }
+        Some(Segment::Elements) if !is_list(field) => {
+            match (fields.list(i)?, segments.get(1)) {
+                (Some(list), Some(Segment::Member(name))) => (0..list.len())
+                    .map(|j| {
+                        select_member(Fields::List(list), j, field, name, &segments[2..])
+                    })
+                    .collect::<parquet::errors::Result<_>>()
+                    .map(Value::List),
+                _ => Ok(Value::Null),
+            }
+        }
+        Some(Segment::Elements) => match (fields.list(i)?, list_element(field)) {
+            (Some(list), Some((_, element))) => (0..list.len())
+                .map(|j| select(Fields::List(list), j, element, &segments[1..]))
+                .collect::<parquet::errors::Result<_>>()
+                .map(Value::List),
+            _ => Ok(Value::Null),
+        },
+    }
+}
+
+/// Selects the value of a member of a group field.
+#[inline]
+fn select_member(
+    fields: Fields,
+    i: usize,
+    field: &Type,
+    name: &str,
+    segments: &[Segment],
+) -> parquet::errors::Result<Value> {
+    let children = field.get_fields();
+    let index = children.iter().position(|c| c.name() == name);
+
+    match (fields.group(i)?, index) {
+        (Some(row), Some(k)) => select(Fields::Row(row), k, &children[k], segments),
+        _ => Ok(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+    use parquet::column::writer::ColumnWriter;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::file::writer::{FileWriter, SerializedFileWriter};
+    use parquet::record::Row;
+    use parquet::schema::parser::parse_message_type;
+    use std::convert::TryFrom;
+    use std::fs;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_path_resolve() {
+        let schema = parse_message_type(api::tests::NESTED_MESSAGE_SCHEMA).unwrap();
+        let resolve = |name| {
+            resolve(&schema, name).map(|c| (c.index, c.name, c.segments, c.field.name()))
+        };
+        let member = |name: &str| Segment::Member(name.to_string());
+
+        assert_eq!(Some((0, String::from("id"), vec![], "id")), resolve("ID"));
+        assert_eq!(
+            Some((
+                1,
+                String::from("address.city"),
+                vec![member("city")],
+                "city"
+            )),
+            resolve("address.City")
+        );
+        assert_eq!(
+            Some((
+                2,
+                String::from("events.element.type"),
+                vec![Segment::Elements, member("type")],
+                "type"
+            )),
+            resolve("events.element.type")
+        );
+        assert_eq!(
+            Some((
+                2,
+                String::from("events.list.element.type"),
+                vec![Segment::Elements, member("type")],
+                "type"
+            )),
+            resolve("events.list.element.type")
+        );
+        assert_eq!(
+            Some((
+                2,
+                String::from("events.type"),
+                vec![Segment::Elements, member("type")],
+                "type"
+            )),
+            resolve("events.type")
+        );
+        assert_eq!(
+            Some((
+                3,
+                String::from("tags.element"),
+                vec![Segment::Elements],
+                "element"
+            )),
+            resolve("tags.element")
+        );
+
+        assert_eq!(None, resolve("address.country"));
+        assert_eq!(None, resolve("id.value"));
+        assert_eq!(None, resolve("unknown.id"));
+    }
+
+    #[test]
+    fn test_path_type_name() {
+        let schema = parse_message_type(
+            "
+            message types {
+                REQUIRED INT64 id;
+                OPTIONAL INT64 ts (TIMESTAMP_MILLIS);
+                OPTIONAL FIXED_LEN_BYTE_ARRAY (16) price (DECIMAL(10,2));
+                REPEATED INT32 codes;
+                OPTIONAL group attrs (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key (UTF8);
+                        OPTIONAL DOUBLE value;
+                    }
+                }
+                OPTIONAL group legacy (LIST) {
+                    REPEATED INT96 array;
+                }
+            }
+            ",
+        )
+        .unwrap();
+        let names = schema
+            .get_fields()
+            .iter()
+            .map(|f| type_name(f))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "INT64",
+                "TIMESTAMP_MILLIS",
+                "DECIMAL(10,2)",
+                "LIST<INT32>",
+                "MAP<UTF8, DOUBLE>",
+                "LIST<INT96>",
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn test_path_column_type_name() {
+        let schema = parse_message_type(api::tests::NESTED_MESSAGE_SCHEMA).unwrap();
+        let type_name = |name| resolve(&schema, name).map(|c| c.type_name());
+
+        assert_eq!(Some(String::from("INT32")), type_name("id"));
+        assert_eq!(Some(String::from("GROUP")), type_name("address"));
+        assert_eq!(Some(String::from("UTF8")), type_name("address.city"));
+        assert_eq!(Some(String::from("LIST<GROUP>")), type_name("events"));
+        assert_eq!(Some(String::from("LIST<UTF8>")), type_name("events.type"));
+        assert_eq!(Some(String::from("LIST<UTF8>")), type_name("tags"));
+    }
+
+    #[test]
+    fn test_path_names() {
+        let schema = parse_message_type(api::tests::NESTED_MESSAGE_SCHEMA).unwrap();
+
+        assert_eq!(
+            vec![
+                "id",
+                "address",
+                "address.city",
+                "address.zip",
+                "events",
+                "events.list",
+                "events.list.element",
+                "events.list.element.type",
+                "tags",
+                "tags.list",
+                "tags.list.element",
+            ],
+            names(&schema)
+        );
+    }
+
+    #[test]
+    fn test_path_select() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("nested.parquet");
+
+        api::tests::write_nested_messages_parquet(&path);
+
+        let reader = SerializedFileReader::try_from(path.as_path()).unwrap();
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        let schema = reader.metadata().file_metadata().schema();
+        let select_column = |row: &Row, i: usize, segments: &[Segment]| {
+            select(Fields::Row(row), i, &schema.get_fields()[i], segments).unwrap()
+        };
+        let city = vec![Segment::Member(String::from("city"))];
+        let types = vec![Segment::Elements, Segment::Member(String::from("type"))];
+
+        assert_eq!(
+            Value::Str(String::from("Paris")),
+            select_column(&rows[0], 1, &city)
+        );
+        assert_eq!(Value::Null, select_column(&rows[1], 1, &city));
+        assert_eq!(
+            Value::List(vec![
+                Value::Str(String::from("click")),
+                Value::Str(String::from("view"))
+            ]),
+            select_column(&rows[0], 2, &types)
+        );
+        assert_eq!(Value::List(vec![]), select_column(&rows[1], 2, &types));
+    }
+
+    #[test]
+    fn test_path_select_repeated_group() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("repeated.parquet");
+        let schema = parse_message_type(
+            "
+            message shapes {
+                REPEATED group points {
+                    REQUIRED INT32 x;
+                }
+                OPTIONAL group shape {
+                    REPEATED group points {
+                        REQUIRED INT32 x;
+                    }
+                }
+            }
+            ",
+        )
+        .unwrap();
+        let props = Rc::new(WriterProperties::builder().build());
+        let file = fs::File::create(&path).unwrap();
+        let mut writer =
+            SerializedFileWriter::new(file, Rc::new(schema.clone()), props).unwrap();
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        let mut index = 0;
+
+        while let Some(mut col_writer) = row_group_writer.next_column().unwrap() {
+            match (index, &mut col_writer) {
+                (0, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[1, 2], Some(&[1, 1, 0]), Some(&[0, 1, 0]))
+                }
+                (1, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[3], Some(&[2, 0]), Some(&[0, 0]))
+                }
+                _ => unreachable!(),
+            }
+            .unwrap();
+
+            row_group_writer.close_column(col_writer).unwrap();
+            index += 1;
+        }
+
+        writer.close_row_group(row_group_writer).unwrap();
+        writer.close().unwrap();
+
+        let points = resolve(&schema, "points.x").unwrap();
+        let shape = resolve(&schema, "shape.points.x").unwrap();
+        let member = |name: &str| Segment::Member(name.to_string());
+
+        assert_eq!(vec![Segment::Elements, member("x")], points.segments);
+        assert_eq!(
+            vec![member("points"), Segment::Elements, member("x")],
+            shape.segments
+        );
+        assert_eq!("LIST<INT32>", points.type_name());
+        assert_eq!("LIST<INT32>", shape.type_name());
+
+        let reader = SerializedFileReader::try_from(path.as_path()).unwrap();
+        let select_column = |row: &Row, column: &Column| {
+            let field = &schema.get_fields()[column.index];
+
+            select(Fields::Row(row), column.index, field, &column.segments).unwrap()
+        };
+        let actual = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| vec![select_column(&row, &points), select_column(&row, &shape)])
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                vec![
+                    Value::List(vec![Value::Int(1), Value::Int(2)]),
+                    Value::List(vec![Value::Int(3)])
+                ],
+                vec![Value::List(vec![]), Value::Null],
+            ],
+            actual
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use crate::filter::Op;
+use crate::value::Value;
+use parquet::basic::{LogicalType, Type as PhysicalType};
+use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::Type;
+use regex::Regex;
+use std::cmp::Ordering;
+
+/// Predicate evaluated against row group statistics.
+///
+/// A predicate can only prove that a row group has no matching rows, anything it can't
+/// reason about is assumed to match.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Predicate {
+    /// Compares the values of a column with a literal.
+    Compare(String, Op, Value),
+
+    /// All predicates may match.
+    And(Vec<Predicate>),
+
+    /// Any predicate may match.
+    Or(Vec<Predicate>),
+}
+
+impl Predicate {
+    /// Returns `false` when the row group statistics prove no row can match.
+    pub fn may_match(&self, row_group: &RowGroupMetaData) -> bool {
+        match self {
+            Predicate::Compare(column, op, value) => row_group
+                .columns()
+                .iter()
+                .find(|c| c.column_path().string() == *column)
+                .and_then(column_range)
+                .map(|range| range_may_match(&range, *op, value))
+                .unwrap_or(true),
+            Predicate::And(vec) => vec.iter().all(|p| p.may_match(row_group)),
+            Predicate::Or(vec) => vec.iter().any(|p| p.may_match(row_group)),
+        }
+    }
+}
+
+/// Checks if any value between min and max may satisfy `column <op> value`.
+#[inline]
+fn range_may_match(range: &(Value, Value), op: Op, value: &Value) -> bool {
+    let min = range.0.compare(value);
+    let max = range.1.compare(value);
+
+    match (op, min, max) {
+        (Op::Eq, Some(min), Some(max)) => {
+            min != Ordering::Greater && max != Ordering::Less
+        }
+        (Op::Ne, Some(min), Some(max)) => {
+            min != Ordering::Equal || max != Ordering::Equal
+        }
+        (Op::Lt, Some(min), _) => min == Ordering::Less,
+        (Op::Le, Some(min), _) => min != Ordering::Greater,
+        (Op::Gt, _, Some(max)) => max == Ordering::Greater,
+        (Op::Ge, _, Some(max)) => max != Ordering::Less,
+        _ => true,
+    }
+}
+
+#[inline]
+fn is_signed(logical_type: LogicalType) -> bool {
+    matches!(
+        logical_type,
+        LogicalType::NONE
+            | LogicalType::INT_8
+            | LogicalType::INT_16
+            | LogicalType::INT_32
+            | LogicalType::INT_64
+    )
+}
+
+#[inline]
+fn is_string(logical_type: LogicalType) -> bool {
+    matches!(
+        logical_type,
+        LogicalType::UTF8 | LogicalType::ENUM | LogicalType::JSON
+    )
+}
+
+/// Gets the min/max statistics of a column chunk as values.
+///
+/// Only statistics with an ordering that matches `Value::compare` are used, byte array
+/// statistics are ignored since some writers, including parquet-rs, order them by
+/// length first.
+fn column_range(column: &ColumnChunkMetaData) -> Option<(Value, Value)> {
+    let logical_type = column.column_descr().logical_type();
+    let stats = column.statistics().filter(|s| s.has_min_max_set())?;
+
+    match stats {
+        Statistics::Boolean(s) => Some((Value::Bool(*s.min()), Value::Bool(*s.max()))),
+        Statistics::Int32(s) if is_signed(logical_type) => Some((
+            Value::Int(i64::from(*s.min())),
+            Value::Int(i64::from(*s.max())),
+        )),
+        Statistics::Int64(s) if is_signed(logical_type) => {
+            Some((Value::Int(*s.min()), Value::Int(*s.max())))
+        }
+        Statistics::Float(s) => Some((Value::Float(*s.min()), Value::Float(*s.max()))),
+        Statistics::Double(s) => Some((Value::Double(*s.min()), Value::Double(*s.max()))),
+        _ => None,
+    }
+}
+
+/// Extracts the literal of an anchored regex without any special characters.
+///
+/// ie: `^foo$` or `^foo\.bar$`
+pub fn regex_literal(regex: &Regex) -> Option<String> {
+    let pattern = regex.as_str();
+
+    if pattern.len() < 2 || !pattern.starts_with('^') || !pattern.ends_with('$') {
+        return None;
+    }
+
+    let mut result = String::new();
+    let mut chars = pattern[1..pattern.len() - 1].chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(e) if e.is_ascii_punctuation() => result.push(e),
+                _ => return None,
+            },
+            '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^'
+            | '$' => return None,
+            c => result.push(c),
+        }
+    }
+
+    Some(result)
+}
+
+/// Converts the text of a cell into a value of the given field type.
+///
+/// The text must be formatted exactly like the cell would be, so strings are expected to
+/// be wrapped in double quotes.
+pub fn literal_value(field: &Type, text: &str) -> Option<Value> {
+    if !field.is_primitive() {
+        return None;
+    }
+
+    let logical_type = field.get_basic_info().logical_type();
+
+    match field.get_physical_type() {
+        PhysicalType::BOOLEAN => text.parse().ok().map(Value::Bool),
+        PhysicalType::INT32 | PhysicalType::INT64 if is_signed(logical_type) => {
+            text.parse().ok().map(Value::Int)
+        }
+        PhysicalType::FLOAT => text.parse().ok().map(Value::Float),
+        PhysicalType::DOUBLE => text.parse().ok().map(Value::Double),
+        PhysicalType::BYTE_ARRAY if is_string(logical_type) => {
+            if text.len() < 2 || !text.starts_with('"') || !text.ends_with('"') {
+                return None;
+            }
+
+            Some(Value::Str(text[1..text.len() - 1].to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::trivial_regex)]
+    fn test_pushdown_regex_literal() {
+        let literal = |s| regex_literal(&Regex::new(s).unwrap());
+
+        assert_eq!(Some(String::from("foo")), literal("^foo$"));
+        assert_eq!(Some(String::from("\"foo bar\"")), literal("^\"foo bar\"$"));
+        assert_eq!(Some(String::from("a.b")), literal("^a\\.b$"));
+        assert_eq!(Some(String::from("-1.5")), literal("^-1\\.5$"));
+        assert_eq!(Some(String::from("")), literal("^$"));
+
+        assert_eq!(None, literal("foo"));
+        assert_eq!(None, literal("^foo"));
+        assert_eq!(None, literal("foo$"));
+        assert_eq!(None, literal("^a.b$"));
+        assert_eq!(None, literal("^a|b$"));
+        assert_eq!(None, literal("^\\d$"));
+        assert_eq!(None, literal("^[ab]$"));
+    }
+
+    #[test]
+    fn test_pushdown_range_may_match() {
+        let range = (Value::Int(10), Value::Int(20));
+        let may_match = |op, value| range_may_match(&range, op, &Value::Int(value));
+
+        assert_eq!(true, may_match(Op::Eq, 10));
+        assert_eq!(true, may_match(Op::Eq, 15));
+        assert_eq!(true, may_match(Op::Eq, 20));
+        assert_eq!(false, may_match(Op::Eq, 9));
+        assert_eq!(false, may_match(Op::Eq, 21));
+
+        assert_eq!(true, may_match(Op::Ne, 10));
+        assert_eq!(
+            false,
+            range_may_match(&(Value::Int(1), Value::Int(1)), Op::Ne, &Value::Int(1))
+        );
+
+        assert_eq!(true, may_match(Op::Lt, 11));
+        assert_eq!(false, may_match(Op::Lt, 10));
+        assert_eq!(true, may_match(Op::Le, 10));
+        assert_eq!(false, may_match(Op::Le, 9));
+
+        assert_eq!(true, may_match(Op::Gt, 19));
+        assert_eq!(false, may_match(Op::Gt, 20));
+        assert_eq!(true, may_match(Op::Ge, 20));
+        assert_eq!(false, may_match(Op::Ge, 21));
+
+        assert_eq!(false, range_may_match(&range, Op::Eq, &Value::Double(20.5)));
+        assert_eq!(true, range_may_match(&range, Op::Lt, &Value::Double(10.5)));
+
+        // values that can't be compared are assumed to match
+        let text = Value::Str(String::from("15"));
+
+        assert_eq!(true, range_may_match(&range, Op::Eq, &text));
+        assert_eq!(true, range_may_match(&range, Op::Gt, &text));
+    }
+}
@@ -1,21 +1,37 @@
 use crate::api::Error;
 use crate::api::Result;
+use crate::filter::{Expr, Filter, Op};
+use crate::path::{self, Segment};
+use crate::pushdown::{self, Predicate};
+use crate::value::{Fields, Value};
 use either::Either;
+use parquet::basic::LogicalType;
+use parquet::column::page::PageReader;
+use parquet::column::reader::ColumnReader;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData, RowGroupMetaData};
 use parquet::file::reader::FileReader;
+use parquet::file::reader::RowGroupReader;
 use parquet::file::reader::SerializedFileReader;
 use parquet::record::reader::RowIter;
 use parquet::record::Row;
-use parquet::record::RowFormatter;
-use parquet::schema::types::Type;
+use parquet::schema::types::{ColumnDescriptor, Type, TypePtr};
 use regex::Regex;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use walkdir::{DirEntry, WalkDir};
 
 pub type ParquetFileReader = SerializedFileReader<File>;
 
+/// Projected schema along with the selected fields and the filter columns.
+type Projection = (Option<Type>, Vec<(usize, String)>, Vec<(usize, String)>);
+
+/// Row position, top-level field type and path of a selected column.
+type Selector = (usize, TypePtr, Vec<Segment>);
+
 #[inline]
 fn create_parquet_reader(path: &Path) -> Result<ParquetFileReader> {
     SerializedFileReader::try_from(path)
@@ -37,54 +53,284 @@ fn file_iterator_num_rows(reader: ParquetFileReader) -> usize {
     iter.count()
 }
 
+/// Levenshtein distance between two names, ignoring case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.to_lowercase().chars().collect::<Vec<_>>();
+    let b = b.to_lowercase().chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            let distance = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+
+            current.push(distance);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Builds an unknown column error suggesting the most similar names.
+pub fn unknown_column(name: &str, names: &[String]) -> Error {
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut candidates = names
+        .iter()
+        .map(|n| (edit_distance(name, n), n))
+        .filter(|t| t.0 <= max_distance)
+        .collect::<Vec<_>>();
+
+    candidates.sort();
+
+    let suggestions = candidates
+        .into_iter()
+        .take(3)
+        .map(|t| t.1.to_string())
+        .collect();
+
+    Error::UnknownColumn(name.to_string(), suggestions)
+}
+
 #[inline]
 fn get_row_fields(
     reader: &ParquetFileReader,
     columns: &Option<Vec<String>>,
-) -> Vec<(usize, String)> {
+) -> Result<Vec<(usize, String)>> {
     let metadata = reader.metadata().file_metadata();
     let schema = metadata.schema();
     let mut result = Vec::new();
-    let fields = schema.get_fields();
-    let enumerate = fields.iter().enumerate();
 
     match columns {
         Some(names) => {
-            let map = enumerate
-                .map(|t| (t.1.name().to_lowercase(), t.0))
-                .collect::<HashMap<_, _>>();
-
             for name in names {
-                if let Some(index) = map.get(&name.to_lowercase()) {
-                    result.push((*index, String::from(fields[*index].name())));
+                match path::resolve(schema, name) {
+                    Some(column) => result.push((column.index, column.name)),
+                    None => return Err(unknown_column(name, &path::names(schema))),
                 }
             }
         }
         None => {
-            for (index, field) in enumerate {
+            for (index, field) in schema.get_fields().iter().enumerate() {
                 result.push((index, String::from(field.name())));
             }
         }
     }
 
-    result
+    Ok(result)
+}
+
+/// Builds a projected schema containing only the selected fields.
+///
+/// Returns the projection and the selected fields re-indexed against the projected row.
+#[inline]
+fn get_row_projection(
+    reader: &ParquetFileReader,
+    fields: &[(usize, String)],
+) -> parquet::errors::Result<(Type, Vec<(usize, String)>)> {
+    let metadata = reader.metadata().file_metadata();
+    let schema = metadata.schema();
+    let schema_fields = schema.get_fields();
+    let mut indexes: Vec<usize> = Vec::new();
+    let mut projected = Vec::new();
+
+    for (index, name) in fields {
+        let position = match indexes.iter().position(|i| i == index) {
+            Some(position) => position,
+            None => {
+                indexes.push(*index);
+                indexes.len() - 1
+            }
+        };
+
+        projected.push((position, name.clone()));
+    }
+
+    let mut types = indexes
+        .iter()
+        .map(|i| schema_fields[*i].clone())
+        .collect::<Vec<TypePtr>>();
+
+    let projection = Type::group_type_builder(schema.name())
+        .with_fields(&mut types)
+        .build()?;
+
+    Ok((projection, projected))
 }
+
+/// Gets the index and name of the columns used by the search filters and the filter
+/// expression.
+///
+/// Filter columns are resolved against the whole schema, so they don't need to be
+/// selected.
+#[inline]
+fn get_filter_columns(
+    reader: &ParquetFileReader,
+    filters: &Option<HashMap<String, Regex>>,
+    expression: &Option<Expr>,
+) -> Result<Vec<(usize, String)>> {
+    let names = filters
+        .iter()
+        .flat_map(|m| m.keys().cloned())
+        .chain(expression.iter().flat_map(Expr::columns))
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        return Ok(vec![]);
+    }
+
+    get_row_fields(reader, &Some(names))
+}
+
+/// Projects the selected fields and the columns used by the filter expression.
+///
+/// Returns the projection and both fields and columns re-indexed against the projected
+/// row, when nothing is selected all columns are read.
+#[inline]
+fn get_projection(
+    reader: &ParquetFileReader,
+    fields: Vec<(usize, String)>,
+    columns: Vec<(usize, String)>,
+) -> parquet::errors::Result<Projection> {
+    if fields.is_empty() && columns.is_empty() {
+        return Ok((None, fields, columns));
+    }
+
+    let num_fields = fields.len();
+    let selected = fields.into_iter().chain(columns).collect::<Vec<_>>();
+    let (projection, mut projected) = get_row_projection(reader, &selected)?;
+    let columns = projected.split_off(num_fields);
+
+    Ok((Some(projection), projected, columns))
+}
+
+/// Gets the row position and path of the selected fields followed by the filter columns.
+///
+/// Returns the selectors and the filter columns re-indexed against the selected values.
+#[inline]
+fn get_row_selectors(
+    reader: &ParquetFileReader,
+    fields: &[(usize, String)],
+    columns: &[(usize, String)],
+) -> (Vec<Selector>, Vec<(usize, String)>) {
+    let metadata = reader.metadata().file_metadata();
+    let schema = metadata.schema();
+    let schema_fields = schema.get_fields();
+    let selectors = fields
+        .iter()
+        .chain(columns)
+        .filter_map(|(i, name)| {
+            let column = path::resolve(schema, name)?;
+
+            Some((*i, schema_fields[column.index].clone(), column.segments))
+        })
+        .collect();
+    let columns = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (fields.len() + i, c.1.clone()))
+        .collect();
+
+    (selectors, columns)
+}
+
+#[inline]
+fn get_row_iter(
+    reader: ParquetFileReader,
+    projection: Option<Type>,
+    row_groups: Vec<usize>,
+) -> parquet::errors::Result<RowIter<'static>> {
+    let file_reader = Box::new(RowGroupFilter { reader, row_groups });
+
+    // the iterator takes ownership of the reader so it can outlive this function
+    RowIter::from_file_into(file_reader).project(projection)
+}
+
+/// Type checks the filter expression against the schema of a file.
+#[inline]
+fn get_row_expression(
+    reader: &ParquetFileReader,
+    expression: &Option<Expr>,
+    columns: &[(usize, String)],
+) -> Result<Option<Filter>> {
+    let metadata = reader.metadata().file_metadata();
+    let schema = metadata.schema();
+
+    expression
+        .as_ref()
+        .map(|e| Filter::bind(e, schema, columns))
+        .transpose()
+}
+
+/// Builds a row group predicate from the filter expression and the search filters that
+/// are equivalent to an equality check.
+#[inline]
+fn get_row_group_predicate(
+    reader: &ParquetFileReader,
+    columns: &[(usize, String)],
+    filters: &Option<HashMap<usize, Regex>>,
+    expression: &Option<Filter>,
+) -> Option<Predicate> {
+    let metadata = reader.metadata().file_metadata();
+    let schema = metadata.schema();
+    let predicates = filters
+        .iter()
+        .flat_map(|m| m.iter())
+        .filter_map(|(i, regex)| {
+            let column = columns.iter().find(|c| c.0 == *i)?;
+            let field = path::resolve(schema, &column.1)?.field;
+            let literal = pushdown::regex_literal(regex)?;
+            let value = pushdown::literal_value(field, &literal)?;
+
+            Some(Predicate::Compare(column.1.clone(), Op::Eq, value))
+        })
+        .chain(expression.iter().filter_map(Filter::predicate))
+        .collect::<Vec<_>>();
+
+    Some(predicates)
+        .filter(|p| !p.is_empty())
+        .map(Predicate::And)
+}
+
+/// Gets the index of the row groups that may contain rows matching the predicate.
+#[inline]
+fn get_row_groups(
+    reader: &ParquetFileReader,
+    predicate: &Option<Predicate>,
+) -> Vec<usize> {
+    let metadata = reader.metadata();
+
+    (0..metadata.num_row_groups())
+        .filter(|i| {
+            predicate
+                .as_ref()
+                .map(|p| p.may_match(metadata.row_group(*i)))
+                .unwrap_or(true)
+        })
+        .collect()
+}
+
+/// Maps the search filters to the index of their column in the row.
 #[inline]
 fn get_row_filters(
-    filelds: &[(usize, String)],
+    columns: &[(usize, String)],
     filters: &Option<HashMap<String, Regex>>,
 ) -> Option<HashMap<usize, Regex>> {
     match filters {
         Some(filter_map) => {
             let mut result = HashMap::new();
-            let field_map = filelds
+            let column_map = columns
                 .iter()
-                .enumerate()
-                .map(|t| ((t.1).1.to_lowercase(), t.0))
+                .map(|c| (c.1.to_lowercase(), c.0))
                 .collect::<HashMap<_, _>>();
 
-            for (field, regex) in filter_map.iter() {
-                if let Some(index) = field_map.get(&field.to_lowercase()) {
+            for (column, regex) in filter_map.iter() {
+                if let Some(index) = column_map.get(&column.to_lowercase()) {
                     result.insert(*index, regex.clone());
                 }
             }
@@ -95,10 +341,131 @@ fn get_row_filters(
     }
 }
 
+/// File reader exposing a subset of the row groups of a parquet file.
+struct RowGroupFilter {
+    reader: ParquetFileReader,
+    row_groups: Vec<usize>,
+}
+
+impl FileReader for RowGroupFilter {
+    fn metadata(&self) -> &ParquetMetaData {
+        self.reader.metadata()
+    }
+
+    fn num_row_groups(&self) -> usize {
+        self.row_groups.len()
+    }
+
+    fn get_row_group(
+        &self,
+        i: usize,
+    ) -> parquet::errors::Result<Box<dyn RowGroupReader + '_>> {
+        let reader = self.reader.get_row_group(self.row_groups[i])?;
+
+        Ok(Box::new(DateColumns::new(reader)?))
+    }
+
+    fn get_row_iter(
+        &self,
+        projection: Option<Type>,
+    ) -> parquet::errors::Result<RowIter<'_>> {
+        RowIter::from_file(projection, self)
+    }
+}
+
+/// Row group reader exposing DATE columns as plain INT32 columns.
+///
+/// The record reader takes the column descriptors from the row group metadata, without
+/// the DATE logical type dates are read as ints that have a typed accessor.
+struct DateColumns<'a> {
+    reader: Box<dyn RowGroupReader + 'a>,
+    metadata: RowGroupMetaData,
+}
+
+impl<'a> DateColumns<'a> {
+    fn new(reader: Box<dyn RowGroupReader + 'a>) -> parquet::errors::Result<Self> {
+        let metadata = reader.metadata();
+        let schema = metadata.schema_descr();
+        let columns = metadata
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let descr = c.column_descr();
+
+                if descr.logical_type() != LogicalType::DATE {
+                    return ColumnChunkMetaData::from_thrift(
+                        c.column_descr_ptr(),
+                        c.to_thrift(),
+                    );
+                }
+
+                let info = descr.self_type().get_basic_info();
+                let mut builder =
+                    Type::primitive_type_builder(descr.name(), descr.physical_type())
+                        .with_repetition(info.repetition());
+
+                if info.has_id() {
+                    builder = builder.with_id(info.id());
+                }
+
+                let descr = ColumnDescriptor::new(
+                    Rc::new(builder.build()?),
+                    Some(schema.get_column_root_ptr(i)),
+                    descr.max_def_level(),
+                    descr.max_rep_level(),
+                    descr.path().clone(),
+                );
+
+                ColumnChunkMetaData::from_thrift(Rc::new(descr), c.to_thrift())
+            })
+            .collect::<parquet::errors::Result<Vec<_>>>()?;
+        let metadata = RowGroupMetaData::builder(metadata.schema_descr_ptr())
+            .set_num_rows(metadata.num_rows())
+            .set_total_byte_size(metadata.total_byte_size())
+            .set_column_metadata(columns)
+            .build()?;
+
+        Ok(Self { reader, metadata })
+    }
+}
+
+impl<'a> RowGroupReader for DateColumns<'a> {
+    fn metadata(&self) -> &RowGroupMetaData {
+        &self.metadata
+    }
+
+    fn num_columns(&self) -> usize {
+        self.reader.num_columns()
+    }
+
+    fn get_column_page_reader(
+        &self,
+        i: usize,
+    ) -> parquet::errors::Result<Box<dyn PageReader>> {
+        self.reader.get_column_page_reader(i)
+    }
+
+    fn get_column_reader(&self, i: usize) -> parquet::errors::Result<ColumnReader> {
+        self.reader.get_column_reader(i)
+    }
+
+    fn get_row_iter(
+        &self,
+        projection: Option<Type>,
+    ) -> parquet::errors::Result<RowIter<'_>> {
+        RowIter::from_row_group(projection, self)
+    }
+}
+
+#[derive(Clone)]
 pub struct ParquetFile {
     path: PathBuf,
     fields: Option<Vec<String>>,
     filters: Option<HashMap<String, Regex>>,
+    expression: Option<Expr>,
+    selection: Option<HashMap<PathBuf, HashSet<usize>>>,
+    row_groups: Cell<(usize, usize)>,
 }
 
 impl ParquetFile {
@@ -107,6 +474,9 @@ impl ParquetFile {
             path,
             fields: None,
             filters: None,
+            expression: None,
+            selection: None,
+            row_groups: Cell::new((0, 0)),
         }
     }
 
@@ -115,6 +485,9 @@ impl ParquetFile {
             fields,
             path: self.path,
             filters: self.filters,
+            expression: self.expression,
+            selection: self.selection,
+            row_groups: self.row_groups,
         }
     }
 
@@ -123,9 +496,45 @@ impl ParquetFile {
             filters,
             path: self.path,
             fields: self.fields,
+            expression: self.expression,
+            selection: self.selection,
+            row_groups: self.row_groups,
+        }
+    }
+
+    pub fn with_expression(self, expression: Option<Expr>) -> Self {
+        Self {
+            expression,
+            path: self.path,
+            fields: self.fields,
+            filters: self.filters,
+            selection: self.selection,
+            row_groups: self.row_groups,
         }
     }
 
+    /// Restricts `iter` to the given row groups of each file, files without any are
+    /// skipped.
+    pub fn with_row_groups(
+        self,
+        selection: Option<HashMap<PathBuf, HashSet<usize>>>,
+    ) -> Self {
+        Self {
+            selection,
+            path: self.path,
+            fields: self.fields,
+            filters: self.filters,
+            expression: self.expression,
+            row_groups: self.row_groups,
+        }
+    }
+
+    /// Number of row groups skipped using statistics and the total number of row groups
+    /// read by `iter`.
+    pub fn skipped_row_groups(&self) -> (usize, usize) {
+        self.row_groups.get()
+    }
+
     pub fn num_rows(&self) -> usize {
         self.files()
             .map(|p| create_parquet_reader(p.as_path()))
@@ -147,7 +556,7 @@ impl ParquetFile {
             .next()
             .map(|p| create_parquet_reader(p.as_path()))
             .map(|r| {
-                let fields = get_row_fields(&r?, &self.fields);
+                let fields = get_row_fields(&r?, &self.fields)?;
                 let names = fields.iter().map(|e| e.1.clone()).collect();
 
                 Ok(names)
@@ -155,6 +564,25 @@ impl ParquetFile {
             .unwrap_or_else(|| Err(Error::from(self.path.to_path_buf())))
     }
 
+    /// Type names of the selected fields, in the order they're returned by `iter`.
+    pub fn field_types(&self) -> Result<Vec<String>> {
+        self.files()
+            .next()
+            .map(|p| {
+                let reader = create_parquet_reader(p.as_path())?;
+                let schema = reader.metadata().file_metadata().schema();
+                let fields = get_row_fields(&reader, &self.fields)?;
+                let types = fields
+                    .iter()
+                    .filter_map(|e| path::resolve(schema, &e.1))
+                    .map(|c| c.type_name())
+                    .collect();
+
+                Ok(types)
+            })
+            .unwrap_or_else(|| Err(Error::from(self.path.to_path_buf())))
+    }
+
     pub fn schema(&self) -> Result<Type> {
         self.files()
             .next()
@@ -163,17 +591,101 @@ impl ParquetFile {
             .unwrap_or_else(|| Err(Error::from(self.path.to_path_buf())))
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = Result<Vec<String>>> + '_ {
-        let iter = self.files();
+    /// Schema of the selected fields, in the order they're returned by `iter`.
+    ///
+    /// Nested column paths select values out of a field, so they can't be part of it.
+    pub fn projected_schema(&self) -> Result<Type> {
+        self.files()
+            .next()
+            .map(|p| {
+                let reader = create_parquet_reader(p.as_path())?;
+                let schema = reader.metadata().file_metadata().schema();
+                let mut types = Vec::new();
+
+                for (index, name) in get_row_fields(&reader, &self.fields)? {
+                    let field = &schema.get_fields()[index];
+
+                    if field.name() != name {
+                        return Err(Error::NestedColumn(name));
+                    }
+
+                    types.push(field.clone());
+                }
+
+                Type::group_type_builder(schema.name())
+                    .with_fields(&mut types)
+                    .build()
+                    .map_err(|e| Error::Parquet(p.to_path_buf(), e))
+            })
+            .unwrap_or_else(|| Err(Error::from(self.path.to_path_buf())))
+    }
+
+    /// Opens a reader for each parquet file under the path.
+    pub fn readers(&self) -> impl Iterator<Item = Result<(PathBuf, ParquetFileReader)>> {
+        self.files().map(|p| {
+            let reader = create_parquet_reader(p.as_path())?;
+
+            Ok((p, reader))
+        })
+    }
+
+    /// Path and index of every row group, read from the file metadata.
+    pub fn row_groups(&self) -> impl Iterator<Item = Result<(PathBuf, usize)>> {
+        self.readers().flat_map(|r| match r {
+            Ok((path, reader)) => (0..reader.num_row_groups())
+                .map(|i| Ok((path.clone(), i)))
+                .collect::<Vec<_>>(),
+            Err(e) => vec![Err(e)],
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Result<Vec<Value>>> + '_ {
+        let iter = self.files().filter(move |p| {
+            self.selection
+                .as_ref()
+                .map(|s| s.contains_key(p))
+                .unwrap_or(true)
+        });
         let field_names = self.fields.clone();
         let field_filter = self.filters.clone();
+        let field_expression = self.expression.clone();
 
         iter.map(move |p| {
             let reader = create_parquet_reader(p.as_path())?;
-            let fields = get_row_fields(&reader, &field_names);
-            let filters = get_row_filters(&fields, &field_filter);
-            let row_iter: RowIter<'static> = reader.into_iter();
-            let iterator: Iter<_> = Iter::new(row_iter, fields, filters);
+            let fields = get_row_fields(&reader, &field_names)?;
+            let columns = get_filter_columns(&reader, &field_filter, &field_expression)?;
+            let (projection, fields, columns) = get_projection(&reader, fields, columns)
+                .map_err(|e| Error::Parquet(p.to_path_buf(), e))?;
+            let (selectors, columns) = get_row_selectors(&reader, &fields, &columns);
+            let filters = get_row_filters(&columns, &field_filter);
+            let expression = get_row_expression(&reader, &field_expression, &columns)?;
+            let predicate =
+                get_row_group_predicate(&reader, &columns, &filters, &expression);
+            let selected = self.selection.as_ref().and_then(|s| s.get(&p));
+            let is_selected = |i: &usize| selected.map(|s| s.contains(i)).unwrap_or(true);
+            let row_groups = get_row_groups(&reader, &predicate)
+                .into_iter()
+                .filter(is_selected)
+                .collect::<Vec<_>>();
+            // only selected row groups can be skipped by the statistics
+            let num_row_groups = (0..reader.num_row_groups()).filter(is_selected).count();
+            let (skipped, total) = self.row_groups.get();
+
+            self.row_groups.set((
+                skipped + num_row_groups - row_groups.len(),
+                total + num_row_groups,
+            ));
+
+            let row_iter = get_row_iter(reader, projection, row_groups)
+                .map_err(|e| Error::Parquet(p.to_path_buf(), e))?;
+            let iterator: Iter<_> = Iter::new(
+                p.to_path_buf(),
+                row_iter,
+                selectors,
+                fields.len(),
+                filters,
+                expression,
+            );
 
             Ok(iterator)
         })
@@ -198,8 +710,10 @@ impl ParquetFile {
                 .unwrap_or(false)
         };
 
+        // sorted so rows are always read in the same order
         WalkDir::new(&self.path)
             .contents_first(true)
+            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
             .into_iter()
             .filter_entry(move |e| is_file || is_parquet(e))
             .filter_map(std::result::Result::ok)
@@ -221,9 +735,12 @@ impl From<(&Path, Option<Vec<String>>)> for ParquetFile {
 }
 
 struct Iter<T> {
-    fields: Vec<(usize, String)>,
+    path: PathBuf,
+    selectors: Vec<Selector>,
+    num_fields: usize,
     values: Either<T, Vec<Error>>,
     filters: Option<HashMap<usize, Regex>>,
+    expression: Option<Filter>,
 }
 
 impl<T> Iter<T>
@@ -231,54 +748,85 @@ where
     T: Iterator<Item = Row>,
 {
     fn new(
+        path: PathBuf,
         values: T,
-        fields: Vec<(usize, String)>,
+        selectors: Vec<Selector>,
+        num_fields: usize,
         filters: Option<HashMap<usize, Regex>>,
+        expression: Option<Filter>,
     ) -> Self {
         Self {
+            path,
             values: Either::Left(values),
             filters,
-            fields,
+            expression,
+            selectors,
+            num_fields,
         }
     }
 
     fn err(error: Error) -> Self {
         Self {
+            path: PathBuf::new(),
             values: Either::Right(vec![error]),
             filters: None,
-            fields: vec![],
+            expression: None,
+            selectors: vec![],
+            num_fields: 0,
         }
     }
 
     fn filter_map_row(
+        path: &Path,
         row: Row,
-        fields: &[(usize, String)],
+        selectors: &[Selector],
+        num_fields: usize,
         filters: &Option<HashMap<usize, Regex>>,
-    ) -> Option<Result<Vec<String>>> {
-        let result = fields
+        expression: &Option<Filter>,
+    ) -> Option<Result<Vec<Value>>> {
+        let fields = Fields::Row(&row);
+        let result = selectors
             .iter()
-            .map(|e| format!("{}", row.fmt(e.0)))
-            .collect::<Vec<_>>();
+            .map(|s| path::select(fields, s.0, &s.1, &s.2))
+            .collect::<parquet::errors::Result<Vec<_>>>();
+        let mut result = match result {
+            Ok(values) => values,
+            Err(e) => return Some(Err(Error::Parquet(path.to_path_buf(), e))),
+        };
 
         if let Some(ref vec) = filters {
             for (i, regex) in vec {
-                if !regex.is_match(&result[*i]) {
+                if !regex.is_match(&result[*i].to_string()) {
                     return None;
                 }
             }
         }
 
+        if let Some(ref filter) = expression {
+            if !filter.is_match(&result) {
+                return None;
+            }
+        }
+
+        // drop the values only selected by filters
+        result.truncate(num_fields);
+
         Some(Ok(result))
     }
 
     fn next_row(
+        path: &Path,
         iter: &mut dyn Iterator<Item = Row>,
-        fields: &[(usize, String)],
+        selectors: &[Selector],
+        num_fields: usize,
         filters: &Option<HashMap<usize, Regex>>,
-    ) -> Option<Result<Vec<String>>> {
+        expression: &Option<Filter>,
+    ) -> Option<Result<Vec<Value>>> {
         // while next try to find a matching row
         for row in iter {
-            if let Some(next) = Iter::<T>::filter_map_row(row, fields, filters) {
+            if let Some(next) = Iter::<T>::filter_map_row(
+                path, row, selectors, num_fields, filters, expression,
+            ) {
                 return Some(next);
             }
         }
@@ -286,7 +834,7 @@ where
         None
     }
 
-    fn next_err(err: &mut Vec<Error>) -> Option<Result<Vec<String>>> {
+    fn next_err(err: &mut Vec<Error>) -> Option<Result<Vec<Value>>> {
         err.pop().map(std::result::Result::Err)
     }
 }
@@ -295,13 +843,18 @@ impl<T> Iterator for Iter<T>
 where
     T: Iterator<Item = Row>,
 {
-    type Item = Result<Vec<String>>;
+    type Item = Result<Vec<Value>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.values {
-            Either::Left(ref mut iter) => {
-                Iter::<T>::next_row(iter, &self.fields, &self.filters)
-            }
+            Either::Left(ref mut iter) => Iter::<T>::next_row(
+                &self.path,
+                iter,
+                &self.selectors,
+                self.num_fields,
+                &self.filters,
+                &self.expression,
+            ),
             Either::Right(ref mut err) => Iter::<T>::next_err(err),
         }
     }
@@ -312,7 +865,7 @@ mod tests {
     use super::*;
     use crate::api;
     use api::tests::time_to_str;
-    use std::fs::File;
+    use std::fs::{self, File};
 
     #[test]
     fn test_path_to_reader() {
@@ -360,13 +913,11 @@ mod tests {
         let parquet_file2 = ParquetFile::from(path2.as_path());
         let parquet_file3 = ParquetFile::from(path3.as_path());
 
-        let mut dir_vec = parquet_dir.files().collect::<Vec<_>>();
+        let dir_vec = parquet_dir.files().collect::<Vec<_>>();
         let file1_vec = parquet_file1.files().collect::<Vec<_>>();
         let file2_vec = parquet_file2.files().collect::<Vec<_>>();
         let file3_vec = parquet_file3.files().collect::<Vec<_>>();
 
-        dir_vec.sort();
-
         assert_eq!(dir_vec.len(), 2);
         assert_eq!(dir_vec, vec![path1.clone(), path2.clone()]);
 
@@ -380,6 +931,40 @@ mod tests {
         assert_eq!(file3_vec, vec![path3.clone()]);
     }
 
+    #[test]
+    fn test_parquet_files_sorted() {
+        let dir = api::tests::temp_dir();
+        let part1 = dir.path().join("day=2");
+        let part2 = dir.path().join("day=10");
+        let paths = vec![
+            dir.path().join("c.parquet"),
+            part1.join("b.parquet"),
+            part1.join("a.parquet"),
+            part2.join("z.parquet"),
+            dir.path().join("a.parquet"),
+        ];
+
+        fs::create_dir(&part1).unwrap();
+        fs::create_dir(&part2).unwrap();
+
+        for path in &paths {
+            File::create(path).unwrap();
+        }
+
+        let actual = ParquetFile::from(dir.path()).files().collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                paths[4].clone(),
+                paths[0].clone(),
+                paths[3].clone(),
+                paths[2].clone(),
+                paths[1].clone(),
+            ],
+            actual
+        );
+    }
+
     #[test]
     fn test_get_row_fields() {
         let dir = api::tests::temp_dir();
@@ -400,7 +985,7 @@ mod tests {
         api::tests::write_simple_messages_parquet(&path, &[msg]);
 
         let reader = create_parquet_reader(&path).unwrap();
-        let result1 = get_row_fields(&reader, &None);
+        let result1 = get_row_fields(&reader, &None).unwrap();
         let result2 = get_row_fields(
             &reader,
             &Some(vec![
@@ -408,7 +993,10 @@ mod tests {
                 String::from("FIELD_INT64"),
                 String::from("field_int32"),
             ]),
-        );
+        )
+        .unwrap();
+        let result3 = get_row_fields(&reader, &Some(vec![String::from("field_in32")]));
+        let result4 = get_row_fields(&reader, &Some(vec![String::from("unknown")]));
 
         assert_eq!(result1.len(), 7);
         assert_eq!(
@@ -433,6 +1021,60 @@ mod tests {
                 (0, String::from("field_int32"))
             ]
         );
+
+        assert_eq!(
+            result3,
+            Err(Error::UnknownColumn(
+                String::from("field_in32"),
+                vec![String::from("field_int32"), String::from("field_int64")]
+            ))
+        );
+        assert_eq!(
+            result4,
+            Err(Error::UnknownColumn(String::from("unknown"), vec![]))
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("name", "NAME"));
+        assert_eq!(1, edit_distance("nme", "name"));
+        assert_eq!(2, edit_distance("naem", "name"));
+        assert_eq!(3, edit_distance("", "abc"));
+        assert_eq!(3, edit_distance("kitten", "sitting"));
+    }
+
+    #[test]
+    fn test_get_row_projection() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("1.snappy.parquet");
+        let msgs = api::tests::create_simple_messages(1);
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        let reader = create_parquet_reader(&path).unwrap();
+        let fields = vec![
+            (6, String::from("field_timestamp")),
+            (1, String::from("field_int64")),
+            (6, String::from("field_timestamp")),
+        ];
+
+        let (projection, projected) = get_row_projection(&reader, &fields).unwrap();
+        let names = projection
+            .get_fields()
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["field_timestamp", "field_int64"]);
+        assert_eq!(
+            projected,
+            vec![
+                (0, String::from("field_timestamp")),
+                (1, String::from("field_int64")),
+                (0, String::from("field_timestamp"))
+            ]
+        );
     }
 
     #[test]
@@ -612,12 +1254,39 @@ mod tests {
         let result = reader.iter().filter_map(Result::ok).collect::<Vec<_>>();
 
         assert_eq!(result.len(), 2);
-        assert_eq!(result[0], vec!["1", "2"]);
-        assert_eq!(result[1], vec!["11", "22"]);
+        assert_eq!(result[0], vec![Value::Int(1), Value::Int(2)]);
+        assert_eq!(result[1], vec![Value::Int(11), Value::Int(22)]);
 
         Ok(())
     }
 
+    #[test]
+    fn test_reader_to_row_iter_projection() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("file.parquet");
+        let msgs = api::tests::create_simple_messages(2);
+
+        api::tests::write_simple_messages_parquet(path.as_path(), &msgs);
+
+        let fields = vec![
+            String::from("field_boolean"),
+            String::from("field_int32"),
+            String::from("field_boolean"),
+        ];
+        let reader = ParquetFile::from(dir.path()).with_fields(Some(fields));
+        let result = reader.iter().filter_map(Result::ok).collect::<Vec<_>>();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0],
+            vec![Value::Bool(false), Value::Int(1), Value::Bool(false)]
+        );
+        assert_eq!(
+            result[1],
+            vec![Value::Bool(true), Value::Int(2), Value::Bool(true)]
+        );
+    }
+
     #[test]
     fn test_reader_to_row_iter_fmt() {
         let dir = api::tests::temp_dir();
@@ -654,6 +1323,30 @@ mod tests {
 
         assert_eq!(
             result[0],
+            vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Float(3.3),
+                Value::Double(4.4),
+                Value::Str(String::from("5")),
+                Value::Bool(true),
+                Value::TimestampMillis(1_238_544_000_000)
+            ]
+        );
+        assert_eq!(
+            result[1],
+            vec![
+                Value::Int(11),
+                Value::Int(22),
+                Value::Float(33.3),
+                Value::Double(44.4),
+                Value::Str(String::from("55")),
+                Value::Bool(false),
+                Value::TimestampMillis(1_238_544_060_000)
+            ]
+        );
+        assert_eq!(
+            result[0].iter().map(Value::to_string).collect::<Vec<_>>(),
             vec![
                 "1",
                 "2",
@@ -664,18 +1357,6 @@ mod tests {
                 &time_to_str(1_238_544_000_000)
             ]
         );
-        assert_eq!(
-            result[1],
-            vec![
-                "11",
-                "22",
-                "33.3",
-                "44.4",
-                "\"55\"",
-                "false",
-                &time_to_str(1_238_544_060_000)
-            ]
-        );
     }
 
     #[test]
@@ -830,7 +1511,318 @@ mod tests {
 
         assert_eq!(result.len(), 2);
 
-        assert_eq!(result[0], vec!["1", "\"odd 1\""]);
-        assert_eq!(result[1], vec!["111", "\"odd 2\""]);
+        assert_eq!(
+            result[0],
+            vec![Value::Int(1), Value::Str(String::from("odd 1"))]
+        );
+        assert_eq!(
+            result[1],
+            vec![Value::Int(111), Value::Str(String::from("odd 2"))]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::trivial_regex)]
+    fn test_reader_row_group_pushdown() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("file.parquet");
+        let msgs = api::tests::create_simple_messages(6);
+
+        api::tests::write_simple_row_groups_parquet(
+            &path,
+            &[&msgs[0..2], &msgs[2..4], &msgs[4..6]],
+        );
+
+        let mut int_filter = HashMap::new();
+        let mut str_filter = HashMap::new();
+        let mut regex_filter = HashMap::new();
+        let fields = vec![String::from("field_int32"), String::from("field_string")];
+
+        int_filter.insert(String::from("field_int32"), Regex::new("^3$").unwrap());
+        str_filter.insert(
+            String::from("field_string"),
+            Regex::new("^\"even 66666\"$").unwrap(),
+        );
+        regex_filter.insert(String::from("field_int32"), Regex::new("^[35]$").unwrap());
+
+        let parquet_int = ParquetFile::from(path.as_path())
+            .with_fields(Some(fields.clone()))
+            .with_filters(Some(int_filter));
+        let parquet_str = ParquetFile::from(path.as_path())
+            .with_fields(Some(fields.clone()))
+            .with_filters(Some(str_filter));
+        let parquet_regex = ParquetFile::from(path.as_path())
+            .with_fields(Some(fields))
+            .with_filters(Some(regex_filter));
+
+        let result_int = parquet_int
+            .iter()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        let result_str = parquet_str
+            .iter()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        let result_regex = parquet_regex
+            .iter()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            result_int,
+            vec![vec![Value::Int(3), Value::Str(String::from("odd 33333"))]]
+        );
+        assert_eq!(
+            result_str,
+            vec![vec![Value::Int(6), Value::Str(String::from("even 66666"))]]
+        );
+        assert_eq!(result_regex.len(), 2);
+
+        assert_eq!((2, 3), parquet_int.skipped_row_groups());
+        // string statistics aren't trusted, so the filter is only applied to the rows
+        assert_eq!((0, 3), parquet_str.skipped_row_groups());
+        assert_eq!((0, 3), parquet_regex.skipped_row_groups());
+    }
+
+    #[test]
+    fn test_reader_row_groups() {
+        let dir = api::tests::temp_dir();
+        let path1 = dir.path().join("1.parquet");
+        let path2 = dir.path().join("2.parquet");
+        let msgs = api::tests::create_simple_messages(6);
+
+        api::tests::write_simple_row_groups_parquet(
+            &path1,
+            &[&msgs[0..2], &msgs[2..4], &msgs[4..6]],
+        );
+        api::tests::write_simple_row_groups_parquet(&path2, &[&msgs[0..1]]);
+
+        let parquet = ParquetFile::from(dir.path())
+            .with_fields(Some(vec![String::from("field_int32")]));
+        let row_groups = parquet.row_groups().collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            vec![
+                (path1.clone(), 0),
+                (path1.clone(), 1),
+                (path1.clone(), 2),
+                (path2, 0),
+            ],
+            row_groups
+        );
+
+        let mut selection = HashMap::new();
+
+        selection.insert(path1, vec![0, 2].into_iter().collect::<HashSet<_>>());
+
+        let pushdown = ParquetFile::from(dir.path())
+            .with_fields(Some(vec![String::from("field_int32")]))
+            .with_expression(Some(Expr::parse("field_int32 >= 5").unwrap()))
+            .with_row_groups(Some(selection.clone()));
+        let selected = parquet.with_row_groups(Some(selection));
+        let result = selected.iter().collect::<Result<Vec<_>>>().unwrap();
+        let pushdown_result = pushdown.iter().collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            vec![
+                vec![Value::Int(1)],
+                vec![Value::Int(2)],
+                vec![Value::Int(5)],
+                vec![Value::Int(6)],
+            ],
+            result
+        );
+        assert_eq!((0, 2), selected.skipped_row_groups());
+        assert_eq!(
+            vec![vec![Value::Int(5)], vec![Value::Int(6)]],
+            pushdown_result
+        );
+        assert_eq!((1, 2), pushdown.skipped_row_groups());
+    }
+
+    #[test]
+    fn test_reader_expression() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("file.parquet");
+        let msgs = api::tests::create_simple_messages(6);
+
+        api::tests::write_simple_row_groups_parquet(
+            &path,
+            &[&msgs[0..2], &msgs[2..4], &msgs[4..6]],
+        );
+
+        let expr = |s| Some(Expr::parse(s).unwrap());
+        let fields = Some(vec![String::from("field_int32")]);
+
+        let parquet_range = ParquetFile::from(path.as_path())
+            .with_fields(fields.clone())
+            .with_expression(expr("field_int64 > 22 AND field_double < 5555.4"));
+        let parquet_or = ParquetFile::from(path.as_path())
+            .with_fields(fields.clone())
+            .with_expression(expr("field_int32 = 1 OR field_int64 = 66"));
+        let parquet_unknown = ParquetFile::from(path.as_path())
+            .with_fields(fields.clone())
+            .with_expression(expr("field_int32 = 1 AND unknown = 1"));
+        let parquet_float_eq = ParquetFile::from(path.as_path())
+            .with_fields(fields.clone())
+            .with_expression(expr("field_float = 111.3"));
+        let parquet_float_ge = ParquetFile::from(path.as_path())
+            .with_fields(fields)
+            .with_expression(expr("field_float >= 444.3"));
+
+        let result_range = parquet_range.iter().collect::<Result<Vec<_>>>();
+        let result_or = parquet_or.iter().collect::<Result<Vec<_>>>();
+        let result_unknown = parquet_unknown.iter().collect::<Result<Vec<_>>>();
+        let result_float_eq = parquet_float_eq.iter().collect::<Result<Vec<_>>>();
+        let result_float_ge = parquet_float_ge.iter().collect::<Result<Vec<_>>>();
+
+        assert_eq!(
+            Ok(vec![vec![Value::Int(3)], vec![Value::Int(4)]]),
+            result_range
+        );
+        assert_eq!(
+            Ok(vec![vec![Value::Int(1)], vec![Value::Int(6)]]),
+            result_or
+        );
+        assert_eq!(
+            Err(Error::UnknownColumn(String::from("unknown"), vec![])),
+            result_unknown
+        );
+        // float literals are compared with the single precision values of the column
+        assert_eq!(Ok(vec![vec![Value::Int(1)]]), result_float_eq);
+        assert_eq!(
+            Ok(vec![
+                vec![Value::Int(4)],
+                vec![Value::Int(5)],
+                vec![Value::Int(6)]
+            ]),
+            result_float_ge
+        );
+
+        assert_eq!((2, 3), parquet_range.skipped_row_groups());
+        assert_eq!((1, 3), parquet_or.skipped_row_groups());
+        assert_eq!((2, 3), parquet_float_eq.skipped_row_groups());
+        assert_eq!((1, 3), parquet_float_ge.skipped_row_groups());
+    }
+
+    #[test]
+    #[allow(clippy::trivial_regex)]
+    fn test_reader_field_filter_not_selected() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("file.parquet");
+        let msgs = api::tests::create_simple_messages(4);
+
+        api::tests::write_simple_messages_parquet(&path, &msgs);
+
+        let mut filters = HashMap::new();
+        let mut unknown = HashMap::new();
+        let fields = Some(vec![String::from("field_int32")]);
+
+        filters.insert(String::from("FIELD_STRING"), Regex::new("even").unwrap());
+        unknown.insert(String::from("unknown"), Regex::new("even").unwrap());
+
+        let result = ParquetFile::from(dir.path())
+            .with_fields(fields.clone())
+            .with_filters(Some(filters))
+            .iter()
+            .collect::<Result<Vec<_>>>();
+        let result_unknown = ParquetFile::from(dir.path())
+            .with_fields(fields)
+            .with_filters(Some(unknown))
+            .iter()
+            .collect::<Result<Vec<_>>>();
+
+        assert_eq!(Ok(vec![vec![Value::Int(2)], vec![Value::Int(4)]]), result);
+        assert_eq!(
+            Err(Error::UnknownColumn(String::from("unknown"), vec![])),
+            result_unknown
+        );
+    }
+
+    #[test]
+    fn test_reader_field_types() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("nested.parquet");
+
+        api::tests::write_nested_messages_parquet(&path);
+
+        let fields = vec![
+            String::from("id"),
+            String::from("address.city"),
+            String::from("events.type"),
+            String::from("tags"),
+        ];
+
+        assert_eq!(
+            Ok(vec![
+                String::from("INT32"),
+                String::from("UTF8"),
+                String::from("LIST<UTF8>"),
+                String::from("LIST<UTF8>"),
+            ]),
+            ParquetFile::from(path.as_path())
+                .with_fields(Some(fields))
+                .field_types()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::trivial_regex)]
+    fn test_reader_nested_columns() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("nested.parquet");
+
+        api::tests::write_nested_messages_parquet(&path);
+
+        let fields = vec![
+            String::from("id"),
+            String::from("Address.City"),
+            String::from("events.element.type"),
+            String::from("tags"),
+        ];
+        let mut filters = HashMap::new();
+
+        filters.insert(String::from("address.zip"), Regex::new("^75$").unwrap());
+
+        let parquet = ParquetFile::from(path.as_path()).with_fields(Some(fields));
+        let parquet_where = ParquetFile::from(path.as_path())
+            .with_fields(Some(vec![String::from("id")]))
+            .with_expression(Some(Expr::parse("events.type = 'view'").unwrap()));
+        let parquet_search = ParquetFile::from(path.as_path())
+            .with_fields(Some(vec![String::from("id")]))
+            .with_filters(Some(filters));
+
+        let click = Value::Str(String::from("click"));
+        let view = Value::Str(String::from("view"));
+
+        assert_eq!(
+            Ok(vec![
+                String::from("id"),
+                String::from("address.city"),
+                String::from("events.element.type"),
+                String::from("tags"),
+            ]),
+            parquet.field_names()
+        );
+        assert_eq!(
+            Ok(vec![
+                vec![
+                    Value::Int(1),
+                    Value::Str(String::from("Paris")),
+                    Value::List(vec![click, view]),
+                    Value::List(vec![Value::Str(String::from("a"))]),
+                ],
+                vec![Value::Int(2), Value::Null, Value::List(vec![]), Value::Null],
+            ]),
+            parquet.iter().collect::<Result<Vec<_>>>()
+        );
+        assert_eq!(
+            Ok(vec![vec![Value::Int(1)]]),
+            parquet_where.iter().collect::<Result<Vec<_>>>()
+        );
+        assert_eq!(
+            Ok(vec![vec![Value::Int(1)]]),
+            parquet_search.iter().collect::<Result<Vec<_>>>()
+        );
     }
 }
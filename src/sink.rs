@@ -0,0 +1,704 @@
+use crate::api::{Error, Result};
+use crate::file;
+use crate::output::OutputFormat;
+use crate::path;
+use crate::value::{Value, JULIAN_DAY_OF_EPOCH, MILLIS_PER_DAY};
+use parquet::basic::{Compression, Repetition};
+use parquet::column::writer::ColumnWriter;
+use parquet::data_type::{ByteArray, Int96};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{FileWriter, SerializedFileWriter};
+use parquet::schema::types::Type;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// Output format name of the parquet sink.
+pub const FORMAT: &str = "parquet";
+
+const DEFAULT_ROW_GROUP_SIZE: usize = 100_000;
+
+/// Output format names accepted by commands that can write parquet.
+pub fn format_values() -> Vec<&'static str> {
+    let mut values = OutputFormat::values();
+
+    values.push(FORMAT);
+
+    values
+}
+
+pub fn compression_values() -> Vec<&'static str> {
+    vec!["uncompressed", "snappy", "gzip", "brotli", "lz4", "zstd"]
+}
+
+pub fn compression_from_str(value: &str) -> Result<Compression> {
+    match value.to_lowercase().as_ref() {
+        "uncompressed" => Ok(Compression::UNCOMPRESSED),
+        "snappy" => Ok(Compression::SNAPPY),
+        "gzip" => Ok(Compression::GZIP),
+        "brotli" => Ok(Compression::BROTLI),
+        "lz4" => Ok(Compression::LZ4),
+        "zstd" => Ok(Compression::ZSTD),
+        _ => Err(Error::InvalidArgument(value.to_string())),
+    }
+}
+
+/// Values and levels of a leaf column, buffered for a row group.
+#[derive(Default)]
+struct ColumnData {
+    values: Vec<Value>,
+    def_levels: Vec<i16>,
+    rep_levels: Vec<i16>,
+}
+
+impl ColumnData {
+    fn push(&mut self, value: Option<&Value>, def: i16, rep: i16) {
+        if let Some(value) = value {
+            self.values.push(value.clone());
+        }
+
+        self.def_levels.push(def);
+        self.rep_levels.push(rep);
+    }
+}
+
+/// Definition and repetition levels of the parent of a field, along with the number of
+/// repeated ancestors.
+#[derive(Clone, Copy, Default)]
+struct Levels {
+    def: i16,
+    rep: i16,
+    depth: i16,
+}
+
+#[inline]
+fn general_err(message: String) -> ParquetError {
+    ParquetError::General(message)
+}
+
+#[inline]
+fn leaves<'a>(field: &'a Type, result: &mut Vec<&'a Type>) {
+    if field.is_primitive() {
+        result.push(field);
+        return;
+    }
+
+    for child in field.get_fields() {
+        leaves(child, result);
+    }
+}
+
+#[inline]
+fn num_leaves(field: &Type) -> usize {
+    if field.is_primitive() {
+        return 1;
+    }
+
+    field.get_fields().iter().map(|f| num_leaves(f)).sum()
+}
+
+#[inline]
+fn push_nulls(columns: &mut [ColumnData], levels: Levels) {
+    for column in columns {
+        column.push(None, levels.def, levels.rep);
+    }
+}
+
+/// Shreds each element of a repeated field, the first element keeps the repetition
+/// level of the parent.
+fn shred_elements<T, F>(
+    elements: &[T],
+    levels: Levels,
+    columns: &mut [ColumnData],
+    shred: F,
+) -> parquet::errors::Result<()>
+where
+    F: Fn(&T, Levels, &mut [ColumnData]) -> parquet::errors::Result<()>,
+{
+    if elements.is_empty() {
+        push_nulls(columns, levels);
+        return Ok(());
+    }
+
+    let depth = levels.depth + 1;
+
+    for (i, element) in elements.iter().enumerate() {
+        let rep = if i == 0 { levels.rep } else { depth };
+        let levels = Levels {
+            def: levels.def + 1,
+            rep,
+            depth,
+        };
+
+        shred(element, levels, columns)?;
+    }
+
+    Ok(())
+}
+
+/// Shreds the value of a field into the values and levels of its leaf columns.
+fn shred_field(
+    field: &Type,
+    value: &Value,
+    levels: Levels,
+    columns: &mut [ColumnData],
+) -> parquet::errors::Result<()> {
+    if field.get_basic_info().repetition() != Repetition::REPEATED {
+        return shred_value(field, value, levels, columns);
+    }
+
+    match value {
+        Value::Null => {
+            shred_elements(&[], levels, columns, |e, l, c| shred_value(field, e, l, c))
+        }
+        Value::List(elements) => shred_elements(elements, levels, columns, |e, l, c| {
+            shred_value(field, e, l, c)
+        }),
+        _ => Err(general_err(format!(
+            "Invalid value {} for repeated field '{}'",
+            value,
+            field.name()
+        ))),
+    }
+}
+
+/// Shreds a single value of a field, repeated fields are already accounted in `levels`.
+fn shred_value(
+    field: &Type,
+    value: &Value,
+    levels: Levels,
+    columns: &mut [ColumnData],
+) -> parquet::errors::Result<()> {
+    let repetition = field.get_basic_info().repetition();
+
+    if *value == Value::Null {
+        if repetition == Repetition::REQUIRED {
+            let message = format!("Null value for required field '{}'", field.name());
+
+            return Err(general_err(message));
+        }
+
+        push_nulls(columns, levels);
+        return Ok(());
+    }
+
+    let levels = Levels {
+        def: levels.def
+            + if repetition == Repetition::OPTIONAL {
+                1
+            } else {
+                0
+            },
+        ..levels
+    };
+
+    if field.is_primitive() {
+        columns[0].push(Some(value), levels.def, levels.rep);
+        return Ok(());
+    }
+
+    let fields = field.get_fields();
+
+    match value {
+        Value::List(elements) if path::is_list(field) => {
+            let (_, element) = path::list_element(field)
+                .ok_or_else(|| general_err(format!("Invalid list '{}'", field.name())))?;
+
+            shred_elements(elements, levels, columns, |e, l, c| {
+                shred_value(element, e, l, c)
+            })
+        }
+        Value::Map(entries) if fields.len() == 1 && fields[0].is_group() => {
+            let key_value = fields[0].get_fields();
+
+            shred_elements(entries, levels, columns, |e, l, c| {
+                let mut offset = 0;
+
+                for (field, value) in key_value.iter().zip(&[&e.0, &e.1]) {
+                    let len = num_leaves(field);
+
+                    shred_field(field, value, l, &mut c[offset..offset + len])?;
+                    offset += len;
+                }
+
+                Ok(())
+            })
+        }
+        Value::Group(values) => {
+            let mut offset = 0;
+
+            for child in fields {
+                let len = num_leaves(child);
+                let value = values
+                    .iter()
+                    .find(|v| v.0 == child.name())
+                    .map(|v| &v.1)
+                    .unwrap_or(&Value::Null);
+
+                shred_field(child, value, levels, &mut columns[offset..offset + len])?;
+                offset += len;
+            }
+
+            Ok(())
+        }
+        _ => Err(general_err(format!(
+            "Invalid value {} for group '{}'",
+            value,
+            field.name()
+        ))),
+    }
+}
+
+#[inline]
+fn bool_value(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(v) => Some(*v),
+        _ => None,
+    }
+}
+
+#[inline]
+fn int32_value(value: &Value) -> Option<i32> {
+    match value {
+        Value::Int(v) => Some(*v as i32),
+        Value::UInt(v) => Some(*v as u32 as i32),
+        Value::Date(v) => Some(*v as i32),
+        Value::Decimal(v, _) => i32::try_from(*v).ok(),
+        _ => None,
+    }
+}
+
+#[inline]
+fn int64_value(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(v) => Some(*v),
+        Value::UInt(v) => Some(*v as i64),
+        Value::TimestampMillis(v) => Some(*v as i64),
+        Value::TimestampMicros(v) => Some(*v as i64),
+        Value::Decimal(v, _) => i64::try_from(*v).ok(),
+        _ => None,
+    }
+}
+
+/// Converts a timestamp into an impala timestamp, nanoseconds of the day and julian day.
+#[inline]
+fn int96_value(value: &Value) -> Option<Int96> {
+    let millis = match value {
+        Value::TimestampMillis(v) => *v,
+        Value::TimestampMicros(v) => *v / 1000,
+        _ => return None,
+    };
+
+    let days = millis / MILLIS_PER_DAY as u64;
+    let nanos = (millis % MILLIS_PER_DAY as u64) * 1_000_000;
+    let mut result = Int96::new();
+
+    result.set_data(
+        nanos as u32,
+        (nanos >> 32) as u32,
+        (days as i64 + JULIAN_DAY_OF_EPOCH) as u32,
+    );
+
+    Some(result)
+}
+
+#[inline]
+fn float_value(value: &Value) -> Option<f32> {
+    match value {
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+#[inline]
+fn double_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Double(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Encodes an unscaled decimal as big-endian two's complement bytes.
+#[inline]
+fn decimal_bytes(unscaled: i128, length: usize) -> Vec<u8> {
+    let bytes = unscaled.to_be_bytes();
+    let sign = if unscaled < 0 { 0xff } else { 0 };
+    let mut result = vec![sign; length.saturating_sub(bytes.len())];
+
+    result.extend_from_slice(&bytes[bytes.len().saturating_sub(length)..]);
+
+    result
+}
+
+/// Minimal number of bytes of the two's complement encoding of an unscaled decimal.
+#[inline]
+fn decimal_length(unscaled: i128) -> usize {
+    let magnitude = if unscaled < 0 { !unscaled } else { unscaled };
+    // one extra bit for the sign, so there is always at least one
+    let bits = 128 - magnitude.leading_zeros() as usize + 1;
+
+    (bits - 1) / 8 + 1
+}
+
+/// Converts a value into a byte array, decimals use the fixed `length` when given and
+/// the minimal length otherwise.
+#[inline]
+fn byte_array_value(value: &Value, length: Option<usize>) -> Option<ByteArray> {
+    match value {
+        Value::Str(v) => Some(ByteArray::from(v.as_bytes().to_vec())),
+        Value::Bytes(v) => Some(ByteArray::from(v.clone())),
+        Value::Decimal(v, _) => {
+            let minimal = decimal_length(*v);
+            let length = length.unwrap_or(minimal);
+
+            // the value doesn't fit the fixed length
+            if length < minimal {
+                return None;
+            }
+
+            Some(ByteArray::from(decimal_bytes(*v, length)))
+        }
+        _ => None,
+    }
+}
+
+#[inline]
+fn convert<T, F>(leaf: &Type, values: &[Value], f: F) -> parquet::errors::Result<Vec<T>>
+where
+    F: Fn(&Value) -> Option<T>,
+{
+    values
+        .iter()
+        .map(|v| {
+            f(v).ok_or_else(|| {
+                general_err(format!("Invalid value {} for column '{}'", v, leaf.name()))
+            })
+        })
+        .collect()
+}
+
+fn write_column(
+    writer: &mut ColumnWriter,
+    leaf: &Type,
+    column: &ColumnData,
+) -> parquet::errors::Result<()> {
+    let def_levels = Some(column.def_levels.as_slice());
+    let rep_levels = Some(column.rep_levels.as_slice());
+    let values = &column.values;
+
+    match writer {
+        ColumnWriter::BoolColumnWriter(ref mut w) => {
+            w.write_batch(&convert(leaf, values, bool_value)?, def_levels, rep_levels)?;
+        }
+        ColumnWriter::Int32ColumnWriter(ref mut w) => {
+            w.write_batch(&convert(leaf, values, int32_value)?, def_levels, rep_levels)?;
+        }
+        ColumnWriter::Int64ColumnWriter(ref mut w) => {
+            w.write_batch(&convert(leaf, values, int64_value)?, def_levels, rep_levels)?;
+        }
+        ColumnWriter::Int96ColumnWriter(ref mut w) => {
+            w.write_batch(&convert(leaf, values, int96_value)?, def_levels, rep_levels)?;
+        }
+        ColumnWriter::FloatColumnWriter(ref mut w) => {
+            w.write_batch(&convert(leaf, values, float_value)?, def_levels, rep_levels)?;
+        }
+        ColumnWriter::DoubleColumnWriter(ref mut w) => {
+            w.write_batch(
+                &convert(leaf, values, double_value)?,
+                def_levels,
+                rep_levels,
+            )?;
+        }
+        ColumnWriter::ByteArrayColumnWriter(ref mut w) => {
+            let values = convert(leaf, values, |v| byte_array_value(v, None))?;
+
+            w.write_batch(&values, def_levels, rep_levels)?;
+        }
+        ColumnWriter::FixedLenByteArrayColumnWriter(ref mut w) => {
+            let length = match leaf {
+                Type::PrimitiveType { type_length, .. } => *type_length as usize,
+                _ => 0,
+            };
+            let values = convert(leaf, values, |v| byte_array_value(v, Some(length)))?;
+
+            w.write_batch(&values, def_levels, rep_levels)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_row_group(
+    writer: &mut dyn FileWriter,
+    schema: &Type,
+    rows: &[Vec<Value>],
+) -> parquet::errors::Result<()> {
+    let mut types = Vec::new();
+
+    leaves(schema, &mut types);
+
+    let mut columns = types
+        .iter()
+        .map(|_| ColumnData::default())
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        let mut offset = 0;
+
+        for (field, value) in schema.get_fields().iter().zip(row) {
+            let len = num_leaves(field);
+            let levels = Levels::default();
+
+            shred_field(field, value, levels, &mut columns[offset..offset + len])?;
+            offset += len;
+        }
+    }
+
+    let mut row_group = writer.next_row_group()?;
+
+    for (leaf, column) in types.iter().zip(&columns) {
+        let mut column_writer = row_group
+            .next_column()?
+            .ok_or_else(|| general_err(format!("Missing column '{}'", leaf.name())))?;
+
+        write_column(&mut column_writer, leaf, column)?;
+        row_group.close_column(column_writer)?;
+    }
+
+    writer.close_row_group(row_group)
+}
+
+/// Writes rows into a new parquet file.
+pub struct ParquetSink {
+    path: PathBuf,
+    compression: Compression,
+    row_group_size: usize,
+    force: bool,
+}
+
+impl ParquetSink {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            compression: Compression::SNAPPY,
+            row_group_size: DEFAULT_ROW_GROUP_SIZE,
+            force: false,
+        }
+    }
+
+    pub fn with_compression(self, compression: Compression) -> Self {
+        Self {
+            compression,
+            path: self.path,
+            row_group_size: self.row_group_size,
+            force: self.force,
+        }
+    }
+
+    pub fn with_row_group_size(self, row_group_size: usize) -> Self {
+        Self {
+            row_group_size,
+            path: self.path,
+            compression: self.compression,
+            force: self.force,
+        }
+    }
+
+    /// Replaces the file when it already exists.
+    pub fn with_force(self, force: bool) -> Self {
+        Self {
+            force,
+            path: self.path,
+            compression: self.compression,
+            row_group_size: self.row_group_size,
+        }
+    }
+
+    /// Writes the rows using the given schema, one value per field of the schema.
+    ///
+    /// Rows are buffered up to the row group size. Returns the number of rows written.
+    pub fn write<I>(&self, schema: Type, iter: I) -> Result<usize>
+    where
+        I: Iterator<Item = Result<Vec<Value>>>,
+    {
+        let file = file::create(&self.path, self.force)?;
+        let props = WriterProperties::builder()
+            .set_compression(self.compression)
+            .set_created_by(format!("xpq version {}", env!("CARGO_PKG_VERSION")))
+            .build();
+        let schema = Rc::new(schema);
+        let mut writer = SerializedFileWriter::new(file, schema.clone(), Rc::new(props))
+            .map_err(|e| Error::Parquet(self.path.clone(), e))?;
+        let row_group_size = self.row_group_size.max(1);
+        let mut rows = Vec::new();
+        let mut count = 0;
+
+        for row in iter {
+            rows.push(row?);
+
+            if rows.len() == row_group_size {
+                write_row_group(&mut writer, &schema, &rows)
+                    .map_err(|e| Error::Parquet(self.path.clone(), e))?;
+                count += rows.len();
+                rows.clear();
+            }
+        }
+
+        if !rows.is_empty() {
+            write_row_group(&mut writer, &schema, &rows)
+                .map_err(|e| Error::Parquet(self.path.clone(), e))?;
+            count += rows.len();
+        }
+
+        writer
+            .close()
+            .map_err(|e| Error::Parquet(self.path.clone(), e))?;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+    use crate::reader::ParquetFile;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_sink_decimal_bytes() {
+        assert_eq!(vec![0x04, 0xd2], decimal_bytes(1234, 2));
+        assert_eq!(vec![0xfb, 0x2e], decimal_bytes(-1234, 2));
+        assert_eq!(vec![0, 0, 0x04, 0xd2], decimal_bytes(1234, 4));
+        assert_eq!(vec![0xff, 0xff, 0xfb, 0x2e], decimal_bytes(-1234, 4));
+        assert_eq!(17, decimal_bytes(-1, 17).len());
+    }
+
+    #[test]
+    fn test_sink_decimal_length() {
+        assert_eq!(1, decimal_length(0));
+        assert_eq!(1, decimal_length(127));
+        assert_eq!(2, decimal_length(128));
+        assert_eq!(1, decimal_length(-128));
+        assert_eq!(2, decimal_length(-129));
+        assert_eq!(2, decimal_length(1234));
+        assert_eq!(16, decimal_length(i128::MAX));
+        assert_eq!(16, decimal_length(i128::MIN));
+    }
+
+    #[test]
+    fn test_sink_decimal_values() {
+        let decimal = |v| Value::Decimal(v, 2);
+        let bytes = |v: &[u8]| Some(ByteArray::from(v.to_vec()));
+
+        assert_eq!(Some(1234), int32_value(&decimal(1234)));
+        assert_eq!(None, int32_value(&decimal(i128::from(i32::MAX) + 1)));
+        assert_eq!(Some(-1234), int64_value(&decimal(-1234)));
+        assert_eq!(None, int64_value(&decimal(i128::from(i64::MIN) - 1)));
+        assert_eq!(bytes(&[0x04, 0xd2]), byte_array_value(&decimal(1234), None));
+        assert_eq!(
+            bytes(&[0xfb, 0x2e]),
+            byte_array_value(&decimal(-1234), None)
+        );
+        assert_eq!(
+            bytes(&[0, 0, 0x04, 0xd2]),
+            byte_array_value(&decimal(1234), Some(4))
+        );
+        assert_eq!(None, byte_array_value(&decimal(1234), Some(1)));
+    }
+
+    #[test]
+    fn test_sink_int96_value() {
+        let mut expected = Int96::new();
+
+        expected.set_data(4_165_425_152, 13, 2_454_923);
+
+        assert_eq!(
+            Some(expected),
+            int96_value(&Value::TimestampMillis(1_238_544_060_000))
+        );
+        assert_eq!(None, int96_value(&Value::Int(1)));
+    }
+
+    #[test]
+    fn test_sink_simple_messages() {
+        let dir = api::tests::temp_dir();
+        let source = dir.path().join("source.parquet");
+        let target = dir.path().join("target.parquet");
+        let msgs = api::tests::create_simple_messages(5);
+
+        api::tests::write_simple_messages_parquet(&source, &msgs);
+
+        let columns = vec![
+            String::from("field_string"),
+            String::from("field_int32"),
+            String::from("field_timestamp"),
+        ];
+        let parquet = ParquetFile::from(source.as_path()).with_fields(Some(columns));
+        let schema = parquet.projected_schema().unwrap();
+        let sink = ParquetSink::new(target.clone())
+            .with_compression(Compression::GZIP)
+            .with_row_group_size(2);
+
+        assert_eq!(5, sink.write(schema, parquet.iter()).unwrap());
+
+        let reader = SerializedFileReader::try_from(target.as_path()).unwrap();
+        let metadata = reader.metadata();
+
+        assert_eq!(3, metadata.num_row_groups());
+        assert_eq!(3, metadata.file_metadata().schema().get_fields().len());
+        assert_eq!(
+            Compression::GZIP,
+            metadata.row_group(0).column(0).compression()
+        );
+
+        let expected = parquet.iter().collect::<Result<Vec<_>>>().unwrap();
+        let actual = ParquetFile::from(target.as_path())
+            .iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_sink_nested_messages() {
+        let dir = api::tests::temp_dir();
+        let source = dir.path().join("source.parquet");
+        let target = dir.path().join("target.parquet");
+
+        api::tests::write_nested_messages_parquet(&source);
+
+        let parquet = ParquetFile::from(source.as_path());
+        let schema = parquet.projected_schema().unwrap();
+        let sink = ParquetSink::new(target.clone());
+
+        assert_eq!(2, sink.write(schema, parquet.iter()).unwrap());
+
+        let expected = parquet.iter().collect::<Result<Vec<_>>>().unwrap();
+        let actual = ParquetFile::from(target.as_path())
+            .iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_sink_nested_column_err() {
+        let dir = api::tests::temp_dir();
+        let source = dir.path().join("source.parquet");
+        let columns = vec![String::from("id"), String::from("address.city")];
+
+        api::tests::write_nested_messages_parquet(&source);
+
+        let parquet = ParquetFile::from(source.as_path()).with_fields(Some(columns));
+
+        assert_eq!(
+            Err(Error::NestedColumn(String::from("address.city"))),
+            parquet.projected_schema()
+        );
+    }
+}
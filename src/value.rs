@@ -0,0 +1,873 @@
+use crate::path;
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use parquet::errors::ParquetError;
+use parquet::record::{List, ListAccessor, MapAccessor, Row, RowAccessor};
+use parquet::schema::types::{Type, TypePtr};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Julian day of the unix epoch, used by INT96 timestamps.
+pub const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
+
+pub const MILLIS_PER_DAY: i64 = 86_400_000;
+
+const DATE_FORMAT: &str = "%Y-%m-%d %:z";
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S %:z";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Typed value of a single parquet cell.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// Null value.
+    Null,
+    /// Boolean value.
+    Bool(bool),
+    /// Signed integer value (INT8 to INT64).
+    Int(i64),
+    /// Unsigned integer value (UINT8 to UINT64).
+    UInt(u64),
+    /// Single precision floating point value.
+    Float(f32),
+    /// Double precision floating point value.
+    Double(f64),
+    /// Decimal value as unscaled integer and scale.
+    Decimal(i128, i32),
+    /// UTF8 string value.
+    Str(String),
+    /// Raw binary value.
+    Bytes(Vec<u8>),
+    /// Date as number of days since the epoch.
+    Date(u32),
+    /// Timestamp as milliseconds since the epoch.
+    TimestampMillis(u64),
+    /// Timestamp as microseconds since the epoch.
+    TimestampMicros(u64),
+    /// List of values.
+    List(Vec<Value>),
+    /// Map of key/value pairs.
+    Map(Vec<(Value, Value)>),
+    /// Group of named values.
+    Group(Vec<(String, Value)>),
+}
+
+/// Text format of date and timestamp values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimestampFormat {
+    /// Custom strftime format.
+    Strftime(String),
+    /// ISO 8601 format, dates without time and timestamps with fractional seconds.
+    Iso8601,
+    /// Number of milliseconds since the epoch.
+    EpochMillis,
+}
+
+/// Text format of binary values.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BinaryFormat {
+    /// Lowercase hexadecimal.
+    Hex,
+    /// Standard base64 with padding.
+    Base64,
+    /// UTF8 text, replacing invalid sequences.
+    Utf8Lossy,
+}
+
+/// Options used when converting values to text.
+///
+/// Options left as `None` keep the default `Display` rendering.
+#[derive(Clone, Debug, Default)]
+pub struct TextOptions {
+    /// Text of null values.
+    pub null: Option<String>,
+    /// Time zone of dates and timestamps, defaults to the local time zone.
+    pub timezone: Option<Tz>,
+    /// Format of dates and timestamps.
+    pub timestamp_format: Option<TimestampFormat>,
+    /// Format of binary values.
+    pub binary: Option<BinaryFormat>,
+}
+
+/// Value displayed using `TextOptions`, created by `Value::display`.
+pub struct Formatted<'a> {
+    value: &'a Value,
+    options: &'a TextOptions,
+}
+
+#[inline]
+fn decimal_unscaled(data: &[u8]) -> i128 {
+    let negative = data.first().map(|b| b & 0x80 != 0).unwrap_or(false);
+    let init: i128 = if negative { -1 } else { 0 };
+
+    data.iter().fold(init, |acc, b| (acc << 8) | i128::from(*b))
+}
+
+#[inline]
+fn format_decimal(unscaled: i128, scale: i32) -> String {
+    if scale <= 0 {
+        let zeros = if unscaled == 0 {
+            String::new()
+        } else {
+            "0".repeat(-scale as usize)
+        };
+
+        return format!("{}{}", unscaled, zeros);
+    }
+
+    let scale = scale as usize;
+    let sign = if unscaled < 0 { "-" } else { "" };
+    let digits = format!("{:0>1$}", unscaled.abs(), scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+
+    format!("{}{}.{}", sign, integer, fraction)
+}
+
+#[inline]
+fn format_float<T>(f: &mut fmt::Formatter, value: T) -> fmt::Result
+where
+    T: Copy + Into<f64> + fmt::Debug + fmt::UpperExp,
+{
+    let abs = value.into().abs();
+
+    if abs != 0.0 && !(1e-15..=1e19).contains(&abs) {
+        return write!(f, "{:E}", value);
+    }
+
+    write!(f, "{:?}", value)
+}
+
+#[inline]
+fn format_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[inline]
+fn format_base64(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.chunks(3).len() * 4);
+
+    for chunk in data.chunks(3) {
+        let byte = |i: usize| u32::from(chunk.get(i).copied().unwrap_or(0));
+        let bits = (byte(0) << 16) | (byte(1) << 8) | byte(2);
+
+        for i in 0..4 {
+            if i > chunk.len() {
+                result.push('=');
+                continue;
+            }
+
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+
+            result.push(char::from(BASE64_ALPHABET[index as usize]));
+        }
+    }
+
+    result
+}
+
+#[inline]
+fn format_datetime<T>(
+    f: &mut fmt::Formatter,
+    dt: DateTime<T>,
+    format: Option<&TimestampFormat>,
+    default: &str,
+    precision: SecondsFormat,
+) -> fmt::Result
+where
+    T: TimeZone,
+    T::Offset: fmt::Display,
+{
+    match format {
+        Some(TimestampFormat::Strftime(pattern)) => write!(f, "{}", dt.format(pattern)),
+        Some(TimestampFormat::Iso8601) => {
+            write!(f, "{}", dt.to_rfc3339_opts(precision, true))
+        }
+        Some(TimestampFormat::EpochMillis) => write!(f, "{}", dt.timestamp_millis()),
+        None => write!(f, "{}", dt.format(default)),
+    }
+}
+
+#[inline]
+fn format_timestamp(
+    f: &mut fmt::Formatter,
+    (secs, nanos): (i64, u32),
+    options: &TextOptions,
+    default: &str,
+    precision: SecondsFormat,
+) -> fmt::Result {
+    let format = options.timestamp_format.as_ref();
+
+    match options.timezone {
+        Some(tz) => {
+            format_datetime(f, tz.timestamp(secs, nanos), format, default, precision)
+        }
+        None => {
+            format_datetime(f, Local.timestamp(secs, nanos), format, default, precision)
+        }
+    }
+}
+
+impl<'a> Formatted<'a> {
+    #[inline]
+    fn nested(&self, value: &'a Value) -> Formatted<'a> {
+        value.display(self.options)
+    }
+}
+
+impl<'a> fmt::Display for Formatted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = self.options;
+
+        match self.value {
+            Value::Null => write!(f, "{}", options.null.as_deref().unwrap_or("null")),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::UInt(value) => write!(f, "{}", value),
+            Value::Float(value) => format_float(f, *value),
+            Value::Double(value) => format_float(f, *value),
+            Value::Decimal(value, scale) => {
+                write!(f, "{}", format_decimal(*value, *scale))
+            }
+            Value::Str(value) => write!(f, "\"{}\"", value),
+            Value::Bytes(value) => match options.binary {
+                Some(BinaryFormat::Hex) => write!(f, "{}", format_hex(value)),
+                Some(BinaryFormat::Base64) => write!(f, "{}", format_base64(value)),
+                Some(BinaryFormat::Utf8Lossy) => {
+                    write!(f, "{}", String::from_utf8_lossy(value))
+                }
+                None => write!(f, "{:?}", value),
+            },
+            Value::Date(value) => {
+                let secs = i64::from(*value) * 86_400;
+
+                // dates have no time zone in iso 8601
+                if options.timestamp_format == Some(TimestampFormat::Iso8601) {
+                    return write!(f, "{}", Utc.timestamp(secs, 0).format("%Y-%m-%d"));
+                }
+
+                format_timestamp(f, (secs, 0), options, DATE_FORMAT, SecondsFormat::Secs)
+            }
+            Value::TimestampMillis(value) => {
+                let secs = (value / 1000) as i64;
+                let nanos = (value % 1000 * 1_000_000) as u32;
+
+                format_timestamp(
+                    f,
+                    (secs, nanos),
+                    options,
+                    TIMESTAMP_FORMAT,
+                    SecondsFormat::Millis,
+                )
+            }
+            Value::TimestampMicros(value) => {
+                let secs = (value / 1_000_000) as i64;
+                let nanos = (value % 1_000_000 * 1000) as u32;
+
+                format_timestamp(
+                    f,
+                    (secs, nanos),
+                    options,
+                    TIMESTAMP_FORMAT,
+                    SecondsFormat::Micros,
+                )
+            }
+            Value::List(values) => {
+                write!(f, "[")?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    self.nested(value).fmt(f)?;
+                }
+
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{} -> {}", self.nested(key), self.nested(value))?;
+                }
+
+                write!(f, "}}")
+            }
+            Value::Group(fields) => {
+                write!(f, "{{")?;
+
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}: {}", name, self.nested(value))?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(&TextOptions::default()).fmt(f)
+    }
+}
+
+impl Value {
+    /// Displays the value as text using the given options.
+    pub fn display<'a>(&'a self, options: &'a TextOptions) -> Formatted<'a> {
+        Formatted {
+            value: self,
+            options,
+        }
+    }
+
+    /// Compares two values, converting between numeric types when needed.
+    ///
+    /// Returns `None` when the values are not comparable.
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+            (Value::Str(a), Value::Str(b)) => a.partial_cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.partial_cmp(b),
+            (Value::Date(a), Value::Date(b)) => a.partial_cmp(b),
+            (Value::TimestampMillis(a), Value::TimestampMillis(b)) => a.partial_cmp(b),
+            (Value::TimestampMicros(a), Value::TimestampMicros(b)) => a.partial_cmp(b),
+            _ => match (self.as_i128(), other.as_i128()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => self.as_f64()?.partial_cmp(&other.as_f64()?),
+            },
+        }
+    }
+
+    #[inline]
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Value::Int(value) => Some(i128::from(*value)),
+            Value::UInt(value) => Some(i128::from(*value)),
+            _ => None,
+        }
+    }
+
+    /// Converts numeric values into a double, `None` for any other value.
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::UInt(value) => Some(*value as f64),
+            Value::Float(value) => Some(f64::from(*value)),
+            Value::Double(value) => Some(*value),
+            Value::Decimal(value, scale) => Some(*value as f64 / 10f64.powi(*scale)),
+            _ => None,
+        }
+    }
+}
+
+/// Fields of a row, the elements of a list or the keys or values of a map.
+///
+/// The record api only exposes fields through typed accessors, so fields are
+/// converted into values using their schema type.
+#[derive(Clone, Copy)]
+pub enum Fields<'a> {
+    /// Fields of a row or group.
+    Row(&'a Row),
+    /// Elements of a list.
+    List(&'a List),
+    /// Keys or values of a map.
+    Entries(&'a dyn ListAccessor),
+}
+
+macro_rules! get {
+    ($fields:expr, $method:ident, $i:expr) => {
+        nullable(match $fields {
+            Fields::Row(row) => row.$method($i),
+            Fields::List(list) => list.$method($i),
+            Fields::Entries(entries) => entries.$method($i),
+        })
+    };
+}
+
+/// Reads a field with a typed accessor, `None` when the field is null.
+///
+/// The accessors fail with "Cannot access Null as ..." for null fields, any other
+/// failure is a field that doesn't match its schema type and is returned as an error.
+#[inline]
+fn nullable<T>(result: parquet::errors::Result<T>) -> parquet::errors::Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ParquetError::General(ref message))
+            if message.starts_with("Cannot access Null as") =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[inline]
+fn group_value(row: &Row, fields: &[TypePtr]) -> parquet::errors::Result<Value> {
+    let values = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Ok((f.name().to_string(), Fields::Row(row).value(i, f)?)))
+        .collect::<parquet::errors::Result<_>>()?;
+
+    Ok(Value::Group(values))
+}
+
+impl<'a> Fields<'a> {
+    /// Gets a group field, `None` when the field is null.
+    #[inline]
+    pub fn group(self, i: usize) -> parquet::errors::Result<Option<&'a Row>> {
+        get!(self, get_group, i)
+    }
+
+    /// Gets a list field, `None` when the field is null.
+    #[inline]
+    pub fn list(self, i: usize) -> parquet::errors::Result<Option<&'a List>> {
+        get!(self, get_list, i)
+    }
+
+    /// Converts a field into a value using its schema type.
+    ///
+    /// Repeated groups are read as lists of their type, fields that don't match their
+    /// type are returned as errors.
+    pub fn value(self, i: usize, field: &Type) -> parquet::errors::Result<Value> {
+        let info = field.get_basic_info();
+
+        if field.is_group()
+            && info.has_repetition()
+            && info.repetition() == Repetition::REPEATED
+        {
+            return match self.list(i)? {
+                Some(list) => (0..list.len())
+                    .map(|j| Fields::List(list).element(j, field))
+                    .collect::<parquet::errors::Result<_>>()
+                    .map(Value::List),
+                None => Ok(Value::Null),
+            };
+        }
+
+        self.element(i, field)
+    }
+
+    fn element(self, i: usize, field: &Type) -> parquet::errors::Result<Value> {
+        if path::is_list(field) {
+            return match (self.list(i)?, path::list_element(field)) {
+                (Some(list), Some((_, element))) => (0..list.len())
+                    .map(|j| Fields::List(list).element(j, element))
+                    .collect::<parquet::errors::Result<_>>()
+                    .map(Value::List),
+                _ => Ok(Value::Null),
+            };
+        }
+
+        if path::is_map(field) {
+            let entries = field.get_fields().first().map(|e| e.get_fields());
+
+            return match (get!(self, get_map, i)?, entries) {
+                (Some(map), Some([key, value])) => {
+                    let keys = map.get_keys();
+                    let values = map.get_values();
+
+                    (0..map.len())
+                        .map(|j| {
+                            Ok((
+                                Fields::Entries(&*keys).value(j, key)?,
+                                Fields::Entries(&*values).value(j, value)?,
+                            ))
+                        })
+                        .collect::<parquet::errors::Result<_>>()
+                        .map(Value::Map)
+                }
+                _ => Ok(Value::Null),
+            };
+        }
+
+        if field.is_group() {
+            return match self.group(i)? {
+                Some(row) => group_value(row, field.get_fields()),
+                None => Ok(Value::Null),
+            };
+        }
+
+        Ok(self.primitive(i, field)?.unwrap_or(Value::Null))
+    }
+
+    /// Reads a primitive field, dates are read as INT32 since the record api has no
+    /// accessor for them.
+    fn primitive(self, i: usize, field: &Type) -> parquet::errors::Result<Option<Value>> {
+        let physical = field.get_physical_type();
+        let logical = field.get_basic_info().logical_type();
+        let value = match (physical, logical) {
+            (_, LogicalType::DECIMAL) => get!(self, get_decimal, i)?
+                .map(|d| Value::Decimal(decimal_unscaled(d.data()), d.scale())),
+            (_, LogicalType::DATE) => {
+                get!(self, get_int, i)?.map(|v| Value::Date(v as u32))
+            }
+            (_, LogicalType::INT_8) => {
+                get!(self, get_byte, i)?.map(|v| Value::Int(i64::from(v)))
+            }
+            (_, LogicalType::INT_16) => {
+                get!(self, get_short, i)?.map(|v| Value::Int(i64::from(v)))
+            }
+            (_, LogicalType::UINT_8) => {
+                get!(self, get_ubyte, i)?.map(|v| Value::UInt(u64::from(v)))
+            }
+            (_, LogicalType::UINT_16) => {
+                get!(self, get_ushort, i)?.map(|v| Value::UInt(u64::from(v)))
+            }
+            (_, LogicalType::UINT_32) => {
+                get!(self, get_uint, i)?.map(|v| Value::UInt(u64::from(v)))
+            }
+            (_, LogicalType::UINT_64) => get!(self, get_ulong, i)?.map(Value::UInt),
+            (_, LogicalType::TIMESTAMP_MILLIS) | (PhysicalType::INT96, _) => {
+                get!(self, get_timestamp_millis, i)?.map(Value::TimestampMillis)
+            }
+            (_, LogicalType::TIMESTAMP_MICROS) => {
+                get!(self, get_timestamp_micros, i)?.map(Value::TimestampMicros)
+            }
+            (PhysicalType::BOOLEAN, _) => get!(self, get_bool, i)?.map(Value::Bool),
+            (PhysicalType::INT32, _) => {
+                get!(self, get_int, i)?.map(|v| Value::Int(i64::from(v)))
+            }
+            (PhysicalType::INT64, _) => get!(self, get_long, i)?.map(Value::Int),
+            (PhysicalType::FLOAT, _) => get!(self, get_float, i)?.map(Value::Float),
+            (PhysicalType::DOUBLE, _) => get!(self, get_double, i)?.map(Value::Double),
+            (PhysicalType::BYTE_ARRAY, LogicalType::UTF8)
+            | (PhysicalType::BYTE_ARRAY, LogicalType::ENUM)
+            | (PhysicalType::BYTE_ARRAY, LogicalType::JSON) => {
+                get!(self, get_string, i)?.map(|v| Value::Str(v.clone()))
+            }
+            _ => get!(self, get_bytes, i)?.map(|v| Value::Bytes(v.data().to_vec())),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api;
+    use crate::api::tests::time_to_str;
+    use crate::reader::ParquetFile;
+    use parquet::column::writer::ColumnWriter;
+    use parquet::data_type::ByteArray;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::{FileWriter, SerializedFileWriter};
+    use parquet::schema::parser::parse_message_type;
+    use std::fs;
+    use std::iter;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_value_from_fields() {
+        let dir = api::tests::temp_dir();
+        let path = dir.path().join("values.parquet");
+        let schema = parse_message_type(
+            "
+            message values {
+                OPTIONAL INT32 byte (INT_8);
+                OPTIONAL INT32 ubyte (UINT_8);
+                OPTIONAL INT64 ulong (UINT_64);
+                OPTIONAL INT32 day (DATE);
+                OPTIONAL INT64 price (DECIMAL(10,2));
+                OPTIONAL BYTE_ARRAY data;
+                OPTIONAL INT64 time (TIMESTAMP_MILLIS);
+                OPTIONAL group days (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT32 element (DATE);
+                    }
+                }
+                OPTIONAL group counts (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key (UTF8);
+                        OPTIONAL INT32 value;
+                    }
+                }
+                OPTIONAL group holidays (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key (UTF8);
+                        OPTIONAL INT32 value (DATE);
+                    }
+                }
+                REPEATED group points {
+                    REQUIRED INT32 x;
+                }
+            }
+            ",
+        )
+        .unwrap();
+        let props = Rc::new(WriterProperties::builder().build());
+        let file = fs::File::create(&path).unwrap();
+        let mut writer =
+            SerializedFileWriter::new(file, Rc::new(schema.clone()), props).unwrap();
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        let mut index = 0;
+
+        while let Some(mut col_writer) = row_group_writer.next_column().unwrap() {
+            match (index, &mut col_writer) {
+                (0, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[-1], Some(&[1, 0]), None)
+                }
+                (1, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[8], Some(&[1, 0]), None)
+                }
+                (2, ColumnWriter::Int64ColumnWriter(typed)) => {
+                    typed.write_batch(&[64], Some(&[1, 0]), None)
+                }
+                (3, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[14_335], Some(&[1, 0]), None)
+                }
+                (4, ColumnWriter::Int64ColumnWriter(typed)) => {
+                    typed.write_batch(&[-1234], Some(&[1, 0]), None)
+                }
+                (5, ColumnWriter::ByteArrayColumnWriter(typed)) => typed.write_batch(
+                    &[ByteArray::from(vec![1, 2, 3])],
+                    Some(&[1, 0]),
+                    None,
+                ),
+                (6, ColumnWriter::Int64ColumnWriter(typed)) => {
+                    typed.write_batch(&[1_238_544_000_000], Some(&[1, 0]), None)
+                }
+                (7, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[1], Some(&[3, 2, 0]), Some(&[0, 1, 0]))
+                }
+                (8, ColumnWriter::ByteArrayColumnWriter(typed)) => typed.write_batch(
+                    &[ByteArray::from("a")],
+                    Some(&[2, 0]),
+                    Some(&[0, 0]),
+                ),
+                (9, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[1], Some(&[3, 0]), Some(&[0, 0]))
+                }
+                (10, ColumnWriter::ByteArrayColumnWriter(typed)) => typed.write_batch(
+                    &[ByteArray::from("new year")],
+                    Some(&[2, 0]),
+                    Some(&[0, 0]),
+                ),
+                (11, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[14_245], Some(&[3, 0]), Some(&[0, 0]))
+                }
+                (12, ColumnWriter::Int32ColumnWriter(typed)) => {
+                    typed.write_batch(&[1], Some(&[1, 0]), Some(&[0, 0]))
+                }
+                _ => unreachable!(),
+            }
+            .unwrap();
+
+            row_group_writer.close_column(col_writer).unwrap();
+            index += 1;
+        }
+
+        writer.close_row_group(row_group_writer).unwrap();
+        writer.close().unwrap();
+
+        let values = vec![
+            Value::Int(-1),
+            Value::UInt(8),
+            Value::UInt(64),
+            Value::Date(14_335),
+            Value::Decimal(-1234, 2),
+            Value::Bytes(vec![1, 2, 3]),
+            Value::TimestampMillis(1_238_544_000_000),
+            Value::List(vec![Value::Date(1), Value::Null]),
+            Value::Map(vec![(Value::Str(String::from("a")), Value::Int(1))]),
+            Value::Map(vec![(
+                Value::Str(String::from("new year")),
+                Value::Date(14_245),
+            )]),
+            Value::List(vec![Value::Group(vec![(String::from("x"), Value::Int(1))])]),
+        ];
+        let nulls = vec![Value::Null; 10]
+            .into_iter()
+            .chain(iter::once(Value::List(vec![])))
+            .collect::<Vec<_>>();
+        let actual = ParquetFile::from(path.as_path())
+            .iter()
+            .collect::<api::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(vec![values, nulls], actual);
+    }
+
+    #[test]
+    fn test_value_nullable() {
+        let error =
+            |name: &str| ParquetError::General(format!("Cannot access {} as Int", name));
+
+        assert_eq!(Ok(Some(1)), nullable(Ok(1)));
+        assert_eq!(Ok(None), nullable::<i32>(Err(error("Null"))));
+        assert_eq!(Err(error("Str")), nullable::<i32>(Err(error("Str"))));
+    }
+
+    #[test]
+    fn test_value_display() {
+        assert_eq!("null", Value::Null.to_string());
+        assert_eq!("false", Value::Bool(false).to_string());
+        assert_eq!("-11", Value::Int(-11).to_string());
+        assert_eq!("22", Value::UInt(22).to_string());
+        assert_eq!("3.3", Value::Float(3.3).to_string());
+        assert_eq!("4.4", Value::Double(4.4).to_string());
+        assert_eq!("1E20", Value::Double(1e20).to_string());
+        assert_eq!("\"5\"", Value::Str(String::from("5")).to_string());
+        assert_eq!("[1, 2]", Value::Bytes(vec![1, 2]).to_string());
+        assert_eq!(
+            time_to_str(1_238_544_000_000),
+            Value::TimestampMillis(1_238_544_000_000).to_string()
+        );
+        assert_eq!(
+            time_to_str(1_238_544_000_000),
+            Value::TimestampMicros(1_238_544_000_000_000).to_string()
+        );
+        assert_eq!(
+            "[1, null]",
+            Value::List(vec![Value::Int(1), Value::Null]).to_string()
+        );
+        assert_eq!(
+            "{\"a\" -> 1}",
+            Value::Map(vec![(Value::Str(String::from("a")), Value::Int(1))]).to_string()
+        );
+        assert_eq!(
+            "{id: 1, name: \"b\"}",
+            Value::Group(vec![
+                (String::from("id"), Value::Int(1)),
+                (String::from("name"), Value::Str(String::from("b")))
+            ])
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_value_display_options() {
+        let utc = TextOptions {
+            null: Some(String::from("NA")),
+            timezone: Some("UTC".parse().unwrap()),
+            ..TextOptions::default()
+        };
+        let iso = TextOptions {
+            timestamp_format: Some(TimestampFormat::Iso8601),
+            ..utc.clone()
+        };
+        let epoch = TextOptions {
+            timestamp_format: Some(TimestampFormat::EpochMillis),
+            ..utc.clone()
+        };
+        let strftime = TextOptions {
+            timestamp_format: Some(TimestampFormat::Strftime(String::from(
+                "%d/%m/%Y %H:%M",
+            ))),
+            ..utc.clone()
+        };
+        let millis = Value::TimestampMillis(1_238_544_000_123);
+        let micros = Value::TimestampMicros(1_238_544_000_123_456);
+        let date = Value::Date(14_335);
+
+        assert_eq!("NA", Value::Null.display(&utc).to_string());
+        assert_eq!(
+            "[1, NA]",
+            Value::List(vec![Value::Int(1), Value::Null])
+                .display(&utc)
+                .to_string()
+        );
+        assert_eq!(
+            "2009-04-01 00:00:00 +00:00",
+            millis.display(&utc).to_string()
+        );
+        assert_eq!("2009-04-01 +00:00", date.display(&utc).to_string());
+        assert_eq!("2009-04-01T00:00:00.123Z", millis.display(&iso).to_string());
+        assert_eq!(
+            "2009-04-01T00:00:00.123456Z",
+            micros.display(&iso).to_string()
+        );
+        assert_eq!("2009-04-01", date.display(&iso).to_string());
+        assert_eq!("1238544000123", millis.display(&epoch).to_string());
+        assert_eq!("1238544000123", micros.display(&epoch).to_string());
+        assert_eq!("1238544000000", date.display(&epoch).to_string());
+        assert_eq!("01/04/2009 00:00", millis.display(&strftime).to_string());
+        assert_eq!("01/04/2009 00:00", date.display(&strftime).to_string());
+    }
+
+    #[test]
+    fn test_value_display_binary() {
+        let bytes = Value::Bytes(b"xpq\xff".to_vec());
+        let options = |binary| TextOptions {
+            binary: Some(binary),
+            ..TextOptions::default()
+        };
+
+        assert_eq!(
+            "787071ff",
+            bytes.display(&options(BinaryFormat::Hex)).to_string()
+        );
+        assert_eq!(
+            "eHBx/w==",
+            bytes.display(&options(BinaryFormat::Base64)).to_string()
+        );
+        assert_eq!(
+            "xpq\u{fffd}",
+            bytes.display(&options(BinaryFormat::Utf8Lossy)).to_string()
+        );
+        assert_eq!("", format_base64(&[]));
+        assert_eq!("Zg==", format_base64(b"f"));
+        assert_eq!("Zm8=", format_base64(b"fo"));
+        assert_eq!("Zm9v", format_base64(b"foo"));
+        assert_eq!("Zm9vYmFy", format_base64(b"foobar"));
+    }
+
+    #[test]
+    fn test_value_compare() {
+        let a = Value::Str(String::from("a"));
+        let b = Value::Str(String::from("b"));
+
+        assert_eq!(Some(Ordering::Less), a.compare(&b));
+        assert_eq!(Some(Ordering::Equal), a.compare(&a));
+        assert_eq!(Some(Ordering::Less), Value::Int(1).compare(&Value::Int(2)));
+        assert_eq!(
+            Some(Ordering::Greater),
+            Value::UInt(2).compare(&Value::Int(-2))
+        );
+        assert_eq!(
+            Some(Ordering::Equal),
+            Value::Int(2).compare(&Value::Double(2.0))
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            Value::Float(1.5).compare(&Value::Int(2))
+        );
+        assert_eq!(
+            Some(Ordering::Greater),
+            Value::Decimal(1234, 2).compare(&Value::Int(12))
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            Value::Bool(false).compare(&Value::Bool(true))
+        );
+        assert_eq!(None, Value::Null.compare(&Value::Int(1)));
+        assert_eq!(None, a.compare(&Value::Int(1)));
+        assert_eq!(None, Value::Double(f64::NAN).compare(&Value::Int(1)));
+    }
+
+    #[test]
+    fn test_value_display_decimal() {
+        assert_eq!("-12.34", Value::Decimal(-1234, 2).to_string());
+        assert_eq!("0.05", Value::Decimal(5, 2).to_string());
+        assert_eq!("-0.005", Value::Decimal(-5, 3).to_string());
+        assert_eq!("1200", Value::Decimal(12, -2).to_string());
+        assert_eq!("42", Value::Decimal(42, 0).to_string());
+        assert_eq!(-1234, decimal_unscaled(&(-1234i32).to_be_bytes()));
+        assert_eq!(1234, decimal_unscaled(&1234i64.to_be_bytes()));
+        assert_eq!(0, decimal_unscaled(&[]));
+    }
+}